@@ -32,15 +32,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Duration: {} days", result.duration_days);
     println!("Threshold Reached: {}", result.threshold_reached);
 
+    if let Some(first_crossing) = result.first_crossing_time {
+        println!("First Threshold Crossing: {}", first_crossing);
+    }
     if let Some(resolution_time) = result.resolution_time {
         println!("Resolution Time: {}", resolution_time);
     }
 
     println!("\n=== Market Statistics ===");
     println!("Active Positions: {}", result.statistics.active_positions);
+    println!("Open Interest: {:.2}", result.statistics.open_interest);
+    println!("Realized PnL: ${:.2}", result.statistics.realized_pnl);
+    println!("Unrealized PnL: ${:.2}", result.statistics.unrealized_pnl);
     if let Some(time_to_res) = result.statistics.time_to_resolution {
         println!("Time to Resolution: {} seconds", time_to_res);
     }
 
+    if let Some(settlement) = &result.settlement {
+        println!("\n=== Settlement ===");
+        println!("Outcome: {:?}", settlement.outcome);
+        println!("Total Payout: ${:.2}", settlement.total_payout);
+        println!("House PnL: ${:.2}", settlement.house_pnl);
+        println!("Funds Conserved: {}", settlement.is_conserved());
+    }
+
+    println!("\n=== Fees ===");
+    println!("Trading Fees: ${:.2}", result.fees.trading_fees);
+    println!("Settlement Fees: ${:.2}", result.fees.settlement_fees);
+    println!("Treasury: ${:.2}", result.fees.treasury);
+    println!("Liquidity Providers: ${:.2}", result.fees.liquidity_providers);
+
     Ok(())
 }
@@ -1,6 +1,9 @@
 //! Scenario comparison example
 
-use preda_market_simulator::{Analytics, Scenario, SimulationConfig, Simulator};
+use preda_market_simulator::{
+    Analytics, ComparisonMetric, Correction, MonteCarloConfig, MonteCarloRunner, Scenario,
+    SimulationConfig, Simulator,
+};
 use std::collections::HashMap;
 
 #[tokio::main]
@@ -16,7 +19,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .threshold(0.75)
         .build()?;
 
-    let simulator = Simulator::new(config);
+    // Run 5 seeded simulations per scenario across all cores
+    let batch = MonteCarloConfig {
+        runs: 5,
+        master_seed: 42,
+        max_parallel: 0,
+    };
+    let runner = MonteCarloRunner::new(Simulator::new(config), batch)?
+        .with_progress(|progress| println!("  Run {}/{}", progress.completed, progress.total));
 
     // Test all scenarios
     let scenarios = Scenario::all();
@@ -27,14 +37,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Description: {}", scenario.description());
 
         let mut scenario_results = Vec::new();
-
-        // Run 5 simulations per scenario
-        for run in 1..=5 {
-            print!("  Run {}/5...", run);
-            let result = simulator.run(scenario).await?;
-            scenario_results.push(result);
-            println!(" Final BSI: {:.4}", scenario_results.last().unwrap().final_bsi);
-        }
+        runner
+            .run(scenario, |result| scenario_results.push(result.clone()))
+            .await?;
 
         results_by_scenario.insert(format!("{:?}", scenario), scenario_results);
         println!();
@@ -62,5 +67,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!();
     }
 
+    // Report final BSI differences that survive multiple-comparison correction
+    println!("=== Significant Final BSI Differences (Holm, alpha = 0.05) ===\n");
+    let matrix = Analytics::pairwise_significance(&results_by_scenario, 0.05, Correction::Holm);
+
+    for test in matrix
+        .tests
+        .iter()
+        .filter(|t| t.metric == ComparisonMetric::FinalBsi && t.significant)
+    {
+        println!(
+            "  {} vs {} ({:?}): p = {:.4}, effect size = {:.2}",
+            test.first, test.second, test.test, test.adjusted_p_value, test.effect_size
+        );
+    }
+
     Ok(())
 }
@@ -1,6 +1,9 @@
 //! Strategy backtesting example
 
-use preda_market_simulator::{Scenario, SimulationConfig, Simulator, Strategy, StrategyBacktest};
+use preda_market_simulator::{
+    BacktestConfig, Backtester, FeeConfig, PositionSizing, Scenario, SimulationConfig, Simulator,
+    Strategy, TimeSeriesConfig,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -13,10 +16,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .initial_bsi(0.5)
         .volatility(0.15)
         .threshold(0.75)
+        .time_series(TimeSeriesConfig {
+            sample_every: 12, // Hourly points
+            max_points: None,
+        })
         .seed(42) // For reproducibility
         .build()?;
 
-    let simulator = Simulator::new(config);
+    let simulator = Simulator::new(config.clone());
+
+    // Backtest against the same market the simulation resolved
+    let backtester = Backtester::new(BacktestConfig {
+        threshold: config.threshold,
+        persistence_hours: config.persistence_hours,
+        resolution_rule: config.resolution_rule,
+        sizing: PositionSizing {
+            max_position_fraction: 0.1,
+            ..PositionSizing::default()
+        },
+        slippage_bps: 10,
+        fees: FeeConfig {
+            taker_fee_bps: 20,
+            ..FeeConfig::default()
+        },
+        ..BacktestConfig::default()
+    })?;
 
     // Define strategies to test
     let strategies = vec![
@@ -31,28 +55,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Strategy::Contrarian { threshold: 0.6 },
     ];
 
-    // Run simulations for each strategy
+    let result = simulator.run(Scenario::Sideways).await?;
+
     for strategy in strategies {
         println!("Testing strategy: {}", strategy.name());
 
-        // Run multiple simulations
-        let mut returns = Vec::new();
-        for _ in 0..10 {
-            let result = simulator.run(Scenario::Sideways).await?;
-            // Simplified return calculation
-            let ret = result.final_bsi - 0.5;
-            returns.push(ret);
-        }
-
-        // Calculate backtest metrics
-        let mut backtest = StrategyBacktest::new(strategy.name());
-        backtest.calculate_metrics(&returns);
+        let backtest = backtester.run_on_result(strategy.build(), &result)?;
 
         println!("  Total Return: {:.4}", backtest.total_return);
+        println!("  Fees Paid: {:.4}", backtest.total_fees);
         println!("  Win Rate: {:.2}%", backtest.win_rate * 100.0);
         println!("  Sharpe Ratio: {:.4}", backtest.sharpe_ratio);
         println!("  Max Drawdown: {:.4}", backtest.max_drawdown);
-        println!("  Number of Trades: {}\n", backtest.num_trades);
+        println!("  Number of Trades: {}", backtest.num_trades);
+        println!("  Outcome: {:?}\n", backtest.outcome);
     }
 
     Ok(())
@@ -0,0 +1,267 @@
+//! Trader models driven by the simulator
+//!
+//! Every simulated participant is an account steered by an [`Agent`]. The six
+//! [`ParticipantBehavior`] models are available as [`BehaviorAgent`]s, and a
+//! [`TradingStrategy`] can join the crowd through a [`StrategyAgent`].
+
+use crate::market::Market;
+use crate::orderbook::{OrderBookConfig, PRICE_TICK};
+use crate::participant::{Participant, ParticipantBehavior};
+use crate::rng::SimRng;
+use crate::settlement::SettlementOutcome;
+use crate::strategy::{MarketObservation, PositionSizing, TradingStrategy};
+use crate::types::{PositionType, Trade};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::fmt::Debug;
+
+/// Market and account state visible to an agent on each tick
+#[derive(Debug, Clone, Copy)]
+pub struct AgentObservation<'a> {
+    /// Tick time
+    pub timestamp: DateTime<Utc>,
+    /// Market being traded
+    pub market: &'a Market,
+    /// The agent's own account: capital and positions
+    pub participant: &'a Participant,
+    /// Order book settings, when trading on an order book venue
+    pub order_book: Option<&'a OrderBookConfig>,
+}
+
+impl AgentObservation<'_> {
+    /// Summary of the observation in the form seen by trading strategies
+    pub fn market_observation(&self) -> MarketObservation {
+        let price = self.market.market_price();
+        MarketObservation {
+            timestamp: self.timestamp,
+            bsi: self.market.current_bsi,
+            market_price: price,
+            position: self.participant.net_position(),
+            capital: self.participant.capital,
+            equity: self.participant.equity(price),
+        }
+    }
+}
+
+/// Order an agent places on a tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgentAction {
+    /// Do not trade
+    Hold,
+    /// Buy `size` contracts of `side` at the market
+    Market { side: PositionType, size: f64 },
+    /// Buy `size` contracts of `side` at a YES price of at most (long) or at
+    /// least (short) `price`; filled at the market without an order book
+    Limit {
+        side: PositionType,
+        size: f64,
+        price: f64,
+    },
+}
+
+/// A trader model driven by the simulator
+///
+/// Agents are stateful and cloned fresh for every run. They decide once per
+/// tick and are told about their fills and the final settlement.
+pub trait Agent: Debug + Send + Sync {
+    /// Agent name used in reports
+    fn name(&self) -> String;
+
+    /// Observe the market and the agent's account and decide what to do
+    fn decide(&mut self, observation: &AgentObservation<'_>, rng: &mut SimRng) -> AgentAction;
+
+    /// Receive a trade executed on the agent's behalf
+    fn on_fill(&mut self, _trade: &Trade) {}
+
+    /// Receive the market outcome and the profit and loss of the settled position
+    fn on_settlement(&mut self, _outcome: SettlementOutcome, _pnl: f64) {}
+
+    /// Clone into a boxed trait object
+    fn clone_box(&self) -> Box<dyn Agent>;
+}
+
+impl Clone for Box<dyn Agent> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Built-in agent trading one of the predefined behavior models
+///
+/// Orders are sized from the account's capital and risk tolerance. On an
+/// order book venue a share of orders rest as limits near the BSI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BehaviorAgent {
+    /// Behavior model
+    pub behavior: ParticipantBehavior,
+}
+
+impl BehaviorAgent {
+    /// Create an agent for a behavior model
+    pub fn new(behavior: ParticipantBehavior) -> Self {
+        BehaviorAgent { behavior }
+    }
+}
+
+impl Agent for BehaviorAgent {
+    fn name(&self) -> String {
+        format!("{:?}", self.behavior)
+    }
+
+    fn decide(&mut self, observation: &AgentObservation<'_>, rng: &mut SimRng) -> AgentAction {
+        let bsi = observation.market.current_bsi;
+        let threshold = observation.market.threshold;
+        if !self.behavior.should_trade(bsi, threshold, rng) {
+            return AgentAction::Hold;
+        }
+
+        let side = self.behavior.position_type(bsi, threshold, rng);
+        let limit_price = observation.order_book.and_then(|config| {
+            rng.gen_bool(config.limit_order_probability).then(|| {
+                let offset = rng.gen_range(0.0..=config.max_price_offset);
+                let price = match side {
+                    PositionType::Long => bsi.value() - offset,
+                    PositionType::Short => bsi.value() + offset,
+                };
+                price.clamp(PRICE_TICK, 1.0 - PRICE_TICK)
+            })
+        });
+
+        let size = observation.participant.calculate_position_size();
+        match limit_price {
+            Some(price) => AgentAction::Limit { side, size, price },
+            None => AgentAction::Market { side, size },
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Agent> {
+        Box::new(*self)
+    }
+}
+
+/// Agent trading a strategy with market orders
+#[derive(Debug, Clone)]
+pub struct StrategyAgent {
+    strategy: Box<dyn TradingStrategy>,
+    sizing: PositionSizing,
+}
+
+impl StrategyAgent {
+    /// Trade `strategy`, converting its actions into orders with `sizing`
+    pub fn new(strategy: Box<dyn TradingStrategy>, sizing: PositionSizing) -> Self {
+        StrategyAgent { strategy, sizing }
+    }
+}
+
+impl Agent for StrategyAgent {
+    fn name(&self) -> String {
+        self.strategy.name()
+    }
+
+    fn decide(&mut self, observation: &AgentObservation<'_>, _rng: &mut SimRng) -> AgentAction {
+        let observation = observation.market_observation();
+        let action = self.strategy.on_tick(&observation);
+        match self.sizing.order(action, &observation) {
+            Some((side, size)) => AgentAction::Market { side, size },
+            None => AgentAction::Hold,
+        }
+    }
+
+    fn on_fill(&mut self, trade: &Trade) {
+        self.strategy.on_fill(trade);
+    }
+
+    fn on_settlement(&mut self, outcome: SettlementOutcome, pnl: f64) {
+        self.strategy.on_settlement(outcome, pnl);
+    }
+
+    fn clone_box(&self) -> Box<dyn Agent> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{default_start_time, SimClock};
+    use crate::rng::{RngStreams, PARTICIPANT_STREAM};
+    use crate::strategy::Strategy;
+    use crate::types::BSI;
+    use chrono::Duration;
+
+    fn market(bsi: f64) -> Market {
+        let clock = SimClock::new(default_start_time(), Duration::minutes(5));
+        Market::new(
+            "test".to_string(),
+            BSI::new(bsi).unwrap(),
+            0.75,
+            clock.interval(Duration::days(1)),
+        )
+    }
+
+    #[test]
+    fn test_behavior_agent_matches_participant_rules() {
+        let market = market(0.3);
+        let participant = Participant::with_risk_tolerance(
+            "p".to_string(),
+            ParticipantBehavior::Aggressive,
+            1000.0,
+            0.5,
+        );
+        let observation = AgentObservation {
+            timestamp: default_start_time(),
+            market: &market,
+            participant: &participant,
+            order_book: None,
+        };
+        let mut agent = BehaviorAgent::new(participant.behavior);
+        let mut rng = RngStreams::new(4).stream(PARTICIPANT_STREAM);
+
+        let actions: Vec<_> = (0..50)
+            .map(|_| agent.decide(&observation, &mut rng))
+            .collect();
+
+        // Aggressive agents buy YES below the threshold, sized at 5% of capital
+        assert!(actions.contains(&AgentAction::Hold));
+        for action in actions {
+            match action {
+                AgentAction::Hold => {}
+                AgentAction::Market { side, size } => {
+                    assert_eq!(side, PositionType::Long);
+                    assert!((size - 50.0).abs() < 1e-9);
+                }
+                AgentAction::Limit { .. } => panic!("limit order without an order book"),
+            }
+        }
+        assert_eq!(agent.name(), "Aggressive");
+    }
+
+    #[test]
+    fn test_strategy_agent_orders_from_signal() {
+        let market = market(0.5);
+        let participant = Participant::with_risk_tolerance(
+            "strategy".to_string(),
+            ParticipantBehavior::Rational,
+            1000.0,
+            0.5,
+        );
+        let observation = AgentObservation {
+            timestamp: default_start_time(),
+            market: &market,
+            participant: &participant,
+            order_book: None,
+        };
+        let strategy = Strategy::ThresholdCrossing { threshold: 0.75 };
+        let mut agent = StrategyAgent::new(strategy.build(), PositionSizing::default());
+        let mut rng = RngStreams::new(4).stream(PARTICIPANT_STREAM);
+
+        // Full-strength long below the threshold: half of equity at 0.5 per contract
+        match agent.decide(&observation, &mut rng) {
+            AgentAction::Market { side, size } => {
+                assert_eq!(side, PositionType::Long);
+                assert!((size - 1000.0).abs() < 1e-9);
+            }
+            action => panic!("unexpected action {:?}", action),
+        }
+    }
+}
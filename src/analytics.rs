@@ -1,7 +1,11 @@
 //! Analytics and performance metrics
 
+use crate::oracle::OracleConfig;
+use crate::significance::{ComparisonMatrix, Correction};
 use crate::simulator::SimulationResult;
 use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+use std::collections::{BTreeMap, HashMap};
 
 /// Analytics engine
 pub struct Analytics;
@@ -9,54 +13,29 @@ pub struct Analytics;
 impl Analytics {
     /// Analyze simulation results
     pub fn analyze(results: &[SimulationResult]) -> PerformanceMetrics {
-        if results.is_empty() {
-            return PerformanceMetrics::default();
+        let mut accumulator = MetricsAccumulator::new();
+        for result in results {
+            accumulator.add(result);
         }
+        accumulator.metrics()
+    }
 
-        let total_runs = results.len();
-        let successful_resolutions = results
-            .iter()
-            .filter(|r| r.threshold_reached)
-            .count();
-
-        let avg_final_bsi = results.iter().map(|r| r.final_bsi).sum::<f64>() / total_runs as f64;
-
-        let avg_volume = results.iter().map(|r| r.total_volume).sum::<f64>() / total_runs as f64;
-
-        let avg_trades = results.iter().map(|r| r.total_trades).sum::<usize>() as f64
-            / total_runs as f64;
-
-        let avg_duration = results
-            .iter()
-            .map(|r| r.duration_days as f64)
-            .sum::<f64>()
-            / total_runs as f64;
-
-        // Calculate BSI volatility
-        let bsi_variance = results
-            .iter()
-            .map(|r| (r.final_bsi - avg_final_bsi).powi(2))
-            .sum::<f64>()
-            / total_runs as f64;
-        let bsi_volatility = bsi_variance.sqrt();
-
-        PerformanceMetrics {
-            total_simulations: total_runs,
-            successful_resolutions,
-            resolution_rate: successful_resolutions as f64 / total_runs as f64,
-            avg_final_bsi,
-            avg_volume,
-            avg_trades,
-            avg_duration_days: avg_duration,
-            bsi_volatility,
+    /// Distributions of resolution, time to resolution, volume and final BSI
+    ///
+    /// Intervals are two-sided at the `confidence` level.
+    pub fn distribution(results: &[SimulationResult], confidence: f64) -> DistributionReport {
+        let mut accumulator = DistributionAccumulator::new();
+        for result in results {
+            accumulator.add(result);
         }
+        accumulator.report(confidence)
     }
 
-    /// Compare multiple scenarios
+    /// Compare multiple scenarios, sorted by scenario name
     pub fn compare_scenarios(
-        results_by_scenario: &std::collections::HashMap<String, Vec<SimulationResult>>,
+        results_by_scenario: &HashMap<String, Vec<SimulationResult>>,
     ) -> Vec<ScenarioComparison> {
-        results_by_scenario
+        let mut comparisons: Vec<ScenarioComparison> = results_by_scenario
             .iter()
             .map(|(scenario, results)| {
                 let metrics = Self::analyze(results);
@@ -65,12 +44,440 @@ impl Analytics {
                     metrics,
                 }
             })
+            .collect();
+        comparisons.sort_by(|a, b| a.scenario_name.cmp(&b.scenario_name));
+        comparisons
+    }
+
+    /// Test whether each pair of scenarios differs on each metric
+    ///
+    /// P-values are corrected across every test in the matrix and compared
+    /// with `alpha`.
+    pub fn pairwise_significance(
+        results_by_scenario: &HashMap<String, Vec<SimulationResult>>,
+        alpha: f64,
+        correction: Correction,
+    ) -> ComparisonMatrix {
+        ComparisonMatrix::new(results_by_scenario, alpha, correction)
+    }
+
+    /// False-resolution rates of each oracle configuration, in order of first appearance
+    pub fn oracle_reliability(results: &[SimulationResult]) -> Vec<OracleReliability> {
+        let mut groups: Vec<(&OracleConfig, Vec<&SimulationResult>)> = Vec::new();
+        for result in results {
+            match groups.iter_mut().find(|(oracle, _)| **oracle == result.oracle) {
+                Some((_, group)) => group.push(result),
+                None => groups.push((&result.oracle, vec![result])),
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(oracle, group)| {
+                let runs = group.len();
+                let rate = |count: usize| count as f64 / runs as f64;
+                let false_positives = group
+                    .iter()
+                    .filter(|r| r.resolution_accuracy.false_positive)
+                    .count();
+                let false_negatives = group
+                    .iter()
+                    .filter(|r| r.resolution_accuracy.false_negative)
+                    .count();
+                let delays: Vec<f64> = group
+                    .iter()
+                    .filter_map(|r| r.resolution_accuracy.detection_delay_secs)
+                    .map(|secs| secs as f64 / 3600.0)
+                    .collect();
+                let avg_detection_delay_hours = (!delays.is_empty())
+                    .then(|| delays.iter().sum::<f64>() / delays.len() as f64);
+
+                OracleReliability {
+                    oracle: oracle.clone(),
+                    runs,
+                    false_positive_rate: rate(false_positives),
+                    false_negative_rate: rate(false_negatives),
+                    avg_detection_delay_hours,
+                }
+            })
             .collect()
     }
+
+    /// Wrong-resolution rates of oracle network runs by number of faulty nodes
+    ///
+    /// A run resolves wrongly when it is a false positive or a false negative.
+    /// The fewest faulty nodes whose rate reaches `confidence` are reported as
+    /// the number needed to force a wrong resolution. Runs without an oracle
+    /// network are skipped.
+    pub fn manipulation_resistance(
+        results: &[SimulationResult],
+        confidence: f64,
+    ) -> ManipulationResistance {
+        let mut counts: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+        for result in results {
+            let Some(network) = &result.oracle_network else {
+                continue;
+            };
+            let accuracy = &result.resolution_accuracy;
+            let entry = counts.entry(network.faulty_nodes).or_default();
+            entry.0 += 1;
+            if accuracy.false_positive || accuracy.false_negative {
+                entry.1 += 1;
+            }
+        }
+
+        let by_faulty_nodes: Vec<FaultyNodeOutcome> = counts
+            .into_iter()
+            .map(|(faulty_nodes, (runs, wrong))| FaultyNodeOutcome {
+                faulty_nodes,
+                runs,
+                wrong_resolution_rate: wrong as f64 / runs as f64,
+            })
+            .collect();
+        let nodes_to_force_wrong_resolution = by_faulty_nodes
+            .iter()
+            .find(|outcome| outcome.faulty_nodes > 0 && outcome.wrong_resolution_rate >= confidence)
+            .map(|outcome| outcome.faulty_nodes);
+
+        ManipulationResistance {
+            by_faulty_nodes,
+            nodes_to_force_wrong_resolution,
+        }
+    }
+}
+
+/// Running totals behind [`PerformanceMetrics`], fed one result at a time
+///
+/// Results can be analyzed as they stream in without being kept in memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsAccumulator {
+    runs: usize,
+    resolutions: usize,
+    final_bsi_mean: f64,
+    final_bsi_m2: f64,
+    volume: f64,
+    trades: usize,
+    duration_days: f64,
+    fees: f64,
+    protocol_revenue: f64,
+}
+
+impl MetricsAccumulator {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a result
+    pub fn add(&mut self, result: &SimulationResult) {
+        self.runs += 1;
+        if result.threshold_reached {
+            self.resolutions += 1;
+        }
+
+        // Welford's update of the final BSI mean and squared deviations
+        let delta = result.final_bsi - self.final_bsi_mean;
+        self.final_bsi_mean += delta / self.runs as f64;
+        self.final_bsi_m2 += delta * (result.final_bsi - self.final_bsi_mean);
+
+        self.volume += result.total_volume;
+        self.trades += result.total_trades;
+        self.duration_days += result.duration_days as f64;
+        self.fees += result.fees.total();
+        self.protocol_revenue += result.fees.treasury;
+    }
+
+    /// Number of results added
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Metrics of the results added so far
+    pub fn metrics(&self) -> PerformanceMetrics {
+        if self.runs == 0 {
+            return PerformanceMetrics::default();
+        }
+
+        let runs = self.runs as f64;
+        PerformanceMetrics {
+            total_simulations: self.runs,
+            successful_resolutions: self.resolutions,
+            resolution_rate: self.resolutions as f64 / runs,
+            avg_final_bsi: self.final_bsi_mean,
+            avg_volume: self.volume / runs,
+            avg_trades: self.trades as f64 / runs,
+            avg_duration_days: self.duration_days / runs,
+            bsi_volatility: (self.final_bsi_m2 / runs).sqrt(),
+            avg_fees: self.fees / runs,
+            avg_protocol_revenue: self.protocol_revenue / runs,
+        }
+    }
+}
+
+/// Per-run outcomes behind a [`DistributionReport`], fed one result at a time
+///
+/// Only the four outcomes of each run are kept, not the results themselves.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DistributionAccumulator {
+    resolutions: usize,
+    time_to_resolution_hours: Vec<f64>,
+    volume: Vec<f64>,
+    final_bsi: Vec<f64>,
+}
+
+impl DistributionAccumulator {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a result
+    pub fn add(&mut self, result: &SimulationResult) {
+        if result.threshold_reached {
+            self.resolutions += 1;
+        }
+        if let Some(secs) = result.statistics.time_to_resolution {
+            self.time_to_resolution_hours.push(secs as f64 / 3600.0);
+        }
+        self.volume.push(result.total_volume);
+        self.final_bsi.push(result.final_bsi);
+    }
+
+    /// Number of results added
+    pub fn runs(&self) -> usize {
+        self.volume.len()
+    }
+
+    /// Report of the results added so far, with intervals at `confidence`
+    pub fn report(&self, confidence: f64) -> DistributionReport {
+        let runs = self.runs();
+        let resolution_rate = match runs {
+            0 => 0.0,
+            n => self.resolutions as f64 / n as f64,
+        };
+
+        DistributionReport {
+            runs,
+            confidence,
+            resolution_rate,
+            resolution_rate_interval: wilson_interval(self.resolutions, runs, confidence),
+            time_to_resolution_hours: DistributionSummary::from_samples(
+                &self.time_to_resolution_hours,
+                confidence,
+            ),
+            volume: DistributionSummary::from_samples(&self.volume, confidence),
+            final_bsi: DistributionSummary::from_samples(&self.final_bsi, confidence),
+        }
+    }
+}
+
+/// Distributions of run outcomes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DistributionReport {
+    /// Number of runs
+    pub runs: usize,
+    /// Confidence level of the intervals
+    pub confidence: f64,
+    /// Fraction of runs that resolved
+    pub resolution_rate: f64,
+    /// Wilson score interval of the resolution rate
+    pub resolution_rate_interval: ConfidenceInterval,
+    /// Hours from start to resolution, over resolved runs only
+    pub time_to_resolution_hours: DistributionSummary,
+    /// Trading volume per run
+    pub volume: DistributionSummary,
+    /// Final BSI per run
+    pub final_bsi: DistributionSummary,
+}
+
+/// Summary of one outcome's distribution across runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DistributionSummary {
+    /// Number of samples
+    pub count: usize,
+    /// Sample mean
+    pub mean: f64,
+    /// Sample standard deviation
+    pub std_dev: f64,
+    /// t-based confidence interval of the mean
+    pub mean_interval: ConfidenceInterval,
+    /// Smallest sample
+    pub min: f64,
+    /// 5th percentile
+    pub p5: f64,
+    /// Median
+    pub median: f64,
+    /// 95th percentile
+    pub p95: f64,
+    /// Largest sample
+    pub max: f64,
+    /// Histogram over `[min, max]`
+    pub histogram: Histogram,
+}
+
+impl DistributionSummary {
+    /// Summarize `samples` with a Sturges-rule histogram and a mean interval at `confidence`
+    pub fn from_samples(samples: &[f64], confidence: f64) -> Self {
+        let count = samples.len();
+        if count == 0 {
+            return DistributionSummary::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let std_dev = match count {
+            1 => 0.0,
+            n => (sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt(),
+        };
+
+        // Degenerate samples give a zero-width interval at the mean
+        let half_width = StudentsT::new(0.0, 1.0, (count - 1) as f64)
+            .map(|t| t.inverse_cdf(0.5 + confidence / 2.0) * std_dev / (count as f64).sqrt())
+            .unwrap_or(0.0);
+        let bins = (count as f64).log2().ceil() as usize + 1;
+
+        DistributionSummary {
+            count,
+            mean,
+            std_dev,
+            mean_interval: ConfidenceInterval {
+                lower: mean - half_width,
+                upper: mean + half_width,
+            },
+            min: sorted[0],
+            p5: quantile(&sorted, 0.05),
+            median: quantile(&sorted, 0.5),
+            p95: quantile(&sorted, 0.95),
+            max: sorted[count - 1],
+            histogram: Histogram::new(&sorted, bins),
+        }
+    }
+}
+
+/// Equal-width histogram
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// Bin edges, one more than the bins; the last bin includes its upper edge
+    pub edges: Vec<f64>,
+    /// Samples in each bin
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Histogram of `samples` in `bins` equal bins spanning their range
+    ///
+    /// Identical samples fall into a single zero-width bin.
+    pub fn new(samples: &[f64], bins: usize) -> Self {
+        if samples.is_empty() || bins == 0 {
+            return Histogram::default();
+        }
+
+        let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let bins = if max > min { bins } else { 1 };
+        let width = (max - min) / bins as f64;
+
+        let mut counts = vec![0; bins];
+        for sample in samples {
+            let bin = match width {
+                0.0 => 0,
+                w => (((sample - min) / w) as usize).min(bins - 1),
+            };
+            counts[bin] += 1;
+        }
+
+        Histogram {
+            edges: (0..=bins).map(|i| min + width * i as f64).collect(),
+            counts,
+        }
+    }
+}
+
+/// Wilson score interval of `successes` out of `trials` at `confidence`
+///
+/// No trials give the uninformative interval `[0, 1]`.
+pub fn wilson_interval(successes: usize, trials: usize, confidence: f64) -> ConfidenceInterval {
+    if trials == 0 {
+        return ConfidenceInterval {
+            lower: 0.0,
+            upper: 1.0,
+        };
+    }
+
+    let z = Normal::new(0.0, 1.0)
+        .map(|normal| normal.inverse_cdf(0.5 + confidence / 2.0))
+        .unwrap_or(0.0);
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let half_width = z / denominator * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+    ConfidenceInterval {
+        lower: (center - half_width).max(0.0),
+        upper: (center + half_width).min(1.0),
+    }
+}
+
+/// Two-sided confidence interval
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    /// Lower bound
+    pub lower: f64,
+    /// Upper bound
+    pub upper: f64,
+}
+
+/// Quantile `q` of ascending `sorted` values, interpolating linearly between ranks
+pub(crate) fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let below = rank.floor() as usize;
+    let above = rank.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
+
+/// Wrong resolutions at one number of faulty nodes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FaultyNodeOutcome {
+    /// Faulty nodes in the network
+    pub faulty_nodes: usize,
+    /// Number of runs
+    pub runs: usize,
+    /// Fraction of runs that resolved wrongly
+    pub wrong_resolution_rate: f64,
+}
+
+/// How many faulty oracle nodes it takes to force a wrong resolution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManipulationResistance {
+    /// Outcomes in order of faulty node count
+    pub by_faulty_nodes: Vec<FaultyNodeOutcome>,
+    /// Fewest faulty nodes whose wrong-resolution rate reached the confidence level
+    pub nodes_to_force_wrong_resolution: Option<usize>,
+}
+
+/// False-resolution statistics for one oracle configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OracleReliability {
+    /// Oracle configuration
+    pub oracle: OracleConfig,
+    /// Number of runs
+    pub runs: usize,
+    /// Fraction of runs resolved although the latent belief never met the rule
+    pub false_positive_rate: f64,
+    /// Fraction of runs where the latent belief met the rule but the market expired
+    pub false_negative_rate: f64,
+    /// Average delay of correct resolutions behind the latent belief, in hours
+    pub avg_detection_delay_hours: Option<f64>,
 }
 
 /// Performance metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
     /// Total number of simulations
     pub total_simulations: usize,
@@ -86,8 +493,12 @@ pub struct PerformanceMetrics {
     pub avg_trades: f64,
     /// Average duration in days
     pub avg_duration_days: f64,
-    /// BSI volatility
+    /// Standard deviation of the final BSI across runs
     pub bsi_volatility: f64,
+    /// Average fees collected per run
+    pub avg_fees: f64,
+    /// Average treasury share of fees per run
+    pub avg_protocol_revenue: f64,
 }
 
 impl Default for PerformanceMetrics {
@@ -101,6 +512,8 @@ impl Default for PerformanceMetrics {
             avg_trades: 0.0,
             avg_duration_days: 0.0,
             bsi_volatility: 0.0,
+            avg_fees: 0.0,
+            avg_protocol_revenue: 0.0,
         }
     }
 }
@@ -119,47 +532,58 @@ mod tests {
     use super::*;
     use crate::scenario::Scenario;
     use crate::market::MarketStatistics;
+    use crate::fees::FeeLedger;
+    use crate::resolution::ResolutionAccuracy;
+    use chrono::Duration;
 
-    #[test]
-    fn test_analytics() {
-        let results = vec![
-            SimulationResult {
-                market_id: "test-1".to_string(),
-                scenario: Scenario::BullishTrend,
-                final_bsi: 0.8,
-                total_volume: 10000.0,
+    fn result(seed: u64, final_bsi: f64, threshold_reached: bool) -> SimulationResult {
+        SimulationResult {
+            market_id: format!("test-{}", seed),
+            seed,
+            scenario: Scenario::BullishTrend,
+            scenario_name: "BullishTrend".to_string(),
+            resolution_rule: "persistence_window".to_string(),
+            final_bsi,
+            total_volume: 10000.0,
+            total_trades: 100,
+            first_crossing_time: None,
+            resolution_time: None,
+            duration_days: 30,
+            threshold_reached,
+            statistics: MarketStatistics {
                 total_trades: 100,
+                total_volume: 10000.0,
+                active_positions: 50,
+                open_interest: 500.0,
+                realized_pnl: 0.0,
+                unrealized_pnl: 0.0,
+                market_price: final_bsi,
+                total_slippage: 0.0,
+                lp_worst_case_loss: None,
+                order_book: None,
+                fees: FeeLedger::default(),
+                current_bsi: final_bsi,
+                threshold: 0.75,
+                time_to_resolution: None,
+                first_crossing_time: None,
                 resolution_time: None,
-                duration_days: 30,
-                threshold_reached: true,
-                statistics: MarketStatistics {
-                    total_trades: 100,
-                    total_volume: 10000.0,
-                    active_positions: 50,
-                    current_bsi: 0.8,
-                    threshold: 0.75,
-                    time_to_resolution: Some(2592000),
-                },
-            },
-            SimulationResult {
-                market_id: "test-2".to_string(),
-                scenario: Scenario::BullishTrend,
-                final_bsi: 0.7,
-                total_volume: 8000.0,
-                total_trades: 80,
-                resolution_time: None,
-                duration_days: 30,
-                threshold_reached: false,
-                statistics: MarketStatistics {
-                    total_trades: 80,
-                    total_volume: 8000.0,
-                    active_positions: 40,
-                    current_bsi: 0.7,
-                    threshold: 0.75,
-                    time_to_resolution: None,
-                },
             },
-        ];
+            time_series: None,
+            participants: Vec::new(),
+            settlement: None,
+            fees: FeeLedger::default(),
+            strategy: None,
+            feedback: None,
+            final_latent_bsi: final_bsi,
+            resolution_accuracy: ResolutionAccuracy::default(),
+            oracle: OracleConfig::default(),
+            oracle_network: None,
+        }
+    }
+
+    #[test]
+    fn test_analytics() {
+        let results = vec![result(1, 0.8, true), result(2, 0.7, false)];
 
         let metrics = Analytics::analyze(&results);
 
@@ -167,4 +591,107 @@ mod tests {
         assert_eq!(metrics.successful_resolutions, 1);
         assert_eq!(metrics.resolution_rate, 0.5);
     }
+
+    #[test]
+    fn test_pairwise_significance() {
+        use crate::significance::{ComparisonMetric, SignificanceTest};
+
+        let group = |offset: f64, resolved: usize| -> Vec<SimulationResult> {
+            (0..20)
+                .map(|i| result(i, offset + i as f64 / 100.0, (i as usize) < resolved))
+                .collect()
+        };
+        let results_by_scenario = HashMap::from([
+            ("low".to_string(), group(0.3, 2)),
+            ("high".to_string(), group(0.7, 18)),
+            ("high-again".to_string(), group(0.7, 18)),
+        ]);
+
+        let matrix =
+            Analytics::pairwise_significance(&results_by_scenario, 0.05, Correction::Holm);
+        let bsi = |first, second| {
+            matrix
+                .get(first, second, ComparisonMetric::FinalBsi, SignificanceTest::WelchT)
+                .unwrap()
+        };
+        let resolution = matrix
+            .get(
+                "low",
+                "high",
+                ComparisonMetric::ResolutionRate,
+                SignificanceTest::TwoProportionZ,
+            )
+            .unwrap();
+
+        assert_eq!(matrix.groups, vec!["high", "high-again", "low"]);
+        // 3 pairs, one proportion test and three tests on each of 3 metrics
+        assert_eq!(matrix.tests.len(), 30);
+        assert_eq!(matrix.tests[0].first, "high");
+        assert_eq!(matrix.tests[0].second, "high-again");
+        assert!(bsi("high", "low").significant);
+        assert!(bsi("high", "low").effect_size > 0.0);
+        assert!(!bsi("high", "high-again").significant);
+        assert_eq!(bsi("high", "high-again").adjusted_p_value, 1.0);
+        assert!(resolution.significant);
+        assert!(resolution.adjusted_p_value >= resolution.p_value);
+        assert_eq!(
+            Analytics::compare_scenarios(&results_by_scenario)[2].scenario_name,
+            "low"
+        );
+    }
+
+    #[test]
+    fn test_distribution_report() {
+        let mut results: Vec<SimulationResult> = (1..=10)
+            .map(|i| result(i, i as f64 / 10.0, i <= 5))
+            .collect();
+        for (i, r) in results.iter_mut().take(5).enumerate() {
+            r.statistics.time_to_resolution = Some(3600 * (i as i64 + 1));
+        }
+
+        let report = Analytics::distribution(&results, 0.95);
+        let bsi = &report.final_bsi;
+
+        assert_eq!(report.runs, 10);
+        assert_eq!(report.resolution_rate, 0.5);
+        assert!((report.resolution_rate_interval.lower - 0.2366).abs() < 1e-4);
+        assert!((report.resolution_rate_interval.upper - 0.7634).abs() < 1e-4);
+        assert_eq!(report.time_to_resolution_hours.count, 5);
+        assert!((report.time_to_resolution_hours.median - 3.0).abs() < 1e-12);
+        assert!((bsi.median - 0.55).abs() < 1e-12);
+        assert!((bsi.p5 - 0.145).abs() < 1e-12);
+        assert!((bsi.p95 - 0.955).abs() < 1e-12);
+        // t(9) quantile 2.262 times the standard error of 0.1..1.0
+        assert!((bsi.mean_interval.upper - bsi.mean - 0.2166).abs() < 1e-3);
+        assert_eq!(bsi.histogram.counts.len(), 5);
+        assert_eq!(bsi.histogram.counts.iter().sum::<usize>(), 10);
+        assert_eq!(report.volume.histogram.counts, vec![10]);
+        assert_eq!(report.volume.mean_interval.lower, 10000.0);
+    }
+
+    #[test]
+    fn test_oracle_reliability_groups_by_config() {
+        let start = crate::clock::default_start_time();
+        let noisy = OracleConfig {
+            observation_noise: 0.1,
+            ..OracleConfig::default()
+        };
+        let mut results = vec![result(1, 0.8, true), result(2, 0.7, false), result(3, 0.8, true)];
+        results[0].resolution_accuracy =
+            ResolutionAccuracy::new(Some(start), Some(start + Duration::hours(2)));
+        results[1].resolution_accuracy = ResolutionAccuracy::new(Some(start), None);
+        results[2].oracle = noisy.clone();
+        results[2].resolution_accuracy = ResolutionAccuracy::new(None, Some(start));
+
+        let reliability = Analytics::oracle_reliability(&results);
+
+        assert_eq!(reliability.len(), 2);
+        assert_eq!(reliability[0].runs, 2);
+        assert_eq!(reliability[0].false_positive_rate, 0.0);
+        assert_eq!(reliability[0].false_negative_rate, 0.5);
+        assert_eq!(reliability[0].avg_detection_delay_hours, Some(2.0));
+        assert_eq!(reliability[1].oracle, noisy);
+        assert_eq!(reliability[1].false_positive_rate, 1.0);
+        assert_eq!(reliability[1].avg_detection_delay_hours, None);
+    }
 }
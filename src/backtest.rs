@@ -0,0 +1,421 @@
+//! Event-driven strategy backtesting against BSI paths
+
+use crate::error::{Result, SimulatorError};
+use crate::fees::FeeConfig;
+use crate::history::BsiSeries;
+use crate::participant::{Participant, ParticipantBehavior};
+use crate::resolution::{ResolutionRuleConfig, RuleStatus};
+use crate::settlement::SettlementOutcome;
+use crate::simulator::SimulationResult;
+use crate::strategy::{
+    EquityPoint, MarketObservation, PositionSizing, StrategyBacktest, TradingStrategy,
+};
+use crate::types::{PositionType, Trade, BSI};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Backtest configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    /// Starting capital
+    pub initial_capital: f64,
+    /// Conversion of strategy actions into orders
+    pub sizing: PositionSizing,
+    /// Price moved against each fill, in bps of the contract price
+    pub slippage_bps: u32,
+    /// BSI threshold for resolution
+    pub threshold: f64,
+    /// Minimum persistence window in hours (for the persistence rule)
+    pub persistence_hours: u32,
+    /// Rule deciding when the market resolves
+    pub resolution_rule: ResolutionRuleConfig,
+    /// Trading and settlement fees
+    pub fees: FeeConfig,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        BacktestConfig {
+            initial_capital: 1000.0,
+            sizing: PositionSizing::default(),
+            slippage_bps: 0,
+            threshold: 0.75,
+            persistence_hours: 24,
+            resolution_rule: ResolutionRuleConfig::default(),
+            fees: FeeConfig::default(),
+        }
+    }
+}
+
+impl BacktestConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if !(self.initial_capital.is_finite() && self.initial_capital > 0.0) {
+            return Err(SimulatorError::InvalidConfig(
+                "Initial capital must be greater than 0".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err(SimulatorError::InvalidConfig(
+                "Threshold must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        self.sizing.validate()?;
+        self.resolution_rule.validate()?;
+        self.fees.validate()
+    }
+}
+
+/// Steps a strategy through a BSI path and settles it at resolution
+#[derive(Debug, Clone)]
+pub struct Backtester {
+    config: BacktestConfig,
+}
+
+impl Backtester {
+    /// Create a backtester
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid
+    pub fn new(config: BacktestConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Backtester { config })
+    }
+
+    /// Backtest against the recorded BSI path of a simulation run
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the run did not record a time series
+    pub fn run_on_result(
+        &self,
+        strategy: Box<dyn TradingStrategy>,
+        result: &SimulationResult,
+    ) -> Result<StrategyBacktest> {
+        let series = result.time_series.as_ref().ok_or_else(|| {
+            SimulatorError::DataError("simulation result has no time series".to_string())
+        })?;
+        self.run(strategy, &series.bsi_path())
+    }
+
+    /// Backtest against a historical BSI series
+    pub fn run_on_series(
+        &self,
+        strategy: Box<dyn TradingStrategy>,
+        series: &BsiSeries,
+    ) -> Result<StrategyBacktest> {
+        self.run(strategy, &series.bsi_path())
+    }
+
+    /// Backtest against a BSI path in time order
+    ///
+    /// The strategy sees each update in turn, priced at the BSI, and its
+    /// actions are sized into fills. The market settles on the first update
+    /// that satisfies the resolution rule, or expires at the end of the path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is empty
+    pub fn run(
+        &self,
+        mut strategy: Box<dyn TradingStrategy>,
+        path: &[(DateTime<Utc>, BSI)],
+    ) -> Result<StrategyBacktest> {
+        let Some(&(last_time, _)) = path.last() else {
+            return Err(SimulatorError::DataError("BSI path is empty".to_string()));
+        };
+
+        let config = &self.config;
+        let mut rule = config
+            .resolution_rule
+            .build(config.threshold, config.persistence_hours);
+        let mut trader = Participant::with_risk_tolerance(
+            strategy.name(),
+            ParticipantBehavior::Rational,
+            config.initial_capital,
+            config.sizing.max_position_fraction,
+        );
+
+        let mut trades = Vec::new();
+        let mut equity_curve = Vec::with_capacity(path.len() + 1);
+        let mut outcome = SettlementOutcome::Expired;
+        let mut settled_at = last_time;
+
+        for &(timestamp, bsi) in path {
+            if rule.observe(bsi, timestamp) == RuleStatus::Met {
+                outcome = SettlementOutcome::Resolved;
+                settled_at = timestamp;
+                break;
+            }
+
+            let observation = MarketObservation {
+                timestamp,
+                bsi,
+                market_price: bsi.value(),
+                position: trader.net_position(),
+                capital: trader.capital,
+                equity: trader.equity(bsi.value()),
+            };
+            let action = strategy.on_tick(&observation);
+            if let Some((side, size)) = config.sizing.order(action, &observation) {
+                let first = trades.len();
+                self.fill(&mut trader, side, size, bsi, timestamp, &mut trades);
+                for trade in &trades[first..] {
+                    strategy.on_fill(trade);
+                }
+            }
+
+            equity_curve.push(EquityPoint {
+                timestamp,
+                equity: trader.equity(bsi.value()),
+                position: trader.net_position(),
+            });
+        }
+
+        // Pay out the open position
+        let mut settlement_pnl = 0.0;
+        if let Some(position) = trader.positions.pop() {
+            let winner = match outcome {
+                SettlementOutcome::Resolved => PositionType::Long,
+                SettlementOutcome::Expired => PositionType::Short,
+            };
+            let payout = if position.position_type == winner {
+                position.size
+            } else {
+                0.0
+            };
+            settlement_pnl = payout - position.cost_basis();
+            trader.capital += payout;
+            trader.realized_pnl += settlement_pnl;
+            trader.pay_fee(config.fees.settlement_fee(payout));
+        }
+        strategy.on_settlement(outcome, settlement_pnl);
+        equity_curve.push(EquityPoint {
+            timestamp: settled_at,
+            equity: trader.capital,
+            position: 0.0,
+        });
+
+        Ok(StrategyBacktest::from_equity_curve(
+            strategy.name(),
+            config.initial_capital,
+            trader.fees_paid,
+            outcome,
+            equity_curve,
+            trades,
+        ))
+    }
+
+    /// Buy `size` contracts of `side` at the BSI plus slippage and fees
+    ///
+    /// The order is capped at what the trader's capital pays for, fees included.
+    fn fill(
+        &self,
+        trader: &mut Participant,
+        side: PositionType,
+        size: f64,
+        bsi: BSI,
+        timestamp: DateTime<Utc>,
+        trades: &mut Vec<Trade>,
+    ) {
+        // Slippage moves the contract price against the trader
+        let unit_price = side.unit_price(bsi.value());
+        let slip = unit_price * self.config.slippage_bps as f64 / 10_000.0;
+        let fill_price = side.unit_price((unit_price + slip).min(1.0));
+        let fee_rate = self.config.fees.trade_fee(1.0, None);
+        let unit_cost = side.unit_price(fill_price) * (1.0 + fee_rate);
+        let size = trader.affordable_size(side, size, unit_cost);
+        if size <= f64::EPSILON {
+            return;
+        }
+
+        for change in trader.apply_fill(side, size, fill_price, timestamp) {
+            let notional = change.size * change.position_type.unit_price(fill_price);
+            let fee = self.config.fees.trade_fee(notional, None);
+            trader.pay_fee(fee);
+            trades.push(Trade {
+                id: format!("backtest-{}", trades.len() + 1),
+                participant_id: trader.id.clone(),
+                trade_type: change.trade_type,
+                position_type: change.position_type,
+                size: change.size,
+                price: fill_price,
+                timestamp,
+                bsi_at_trade: bsi,
+                realized_pnl: change.realized_pnl,
+                slippage: change.size * slip,
+                fee,
+                order_id: None,
+                liquidity_role: None,
+                partial_fill: false,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+    use crate::strategy::{Strategy, StrategyAction};
+    use chrono::Duration;
+
+    fn path(values: &[f64]) -> Vec<(DateTime<Utc>, BSI)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                (
+                    default_start_time() + Duration::hours(i as i64),
+                    BSI::new(v).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_long_strategy_wins_on_resolution() {
+        let config = BacktestConfig {
+            resolution_rule: ResolutionRuleConfig::FirstCrossing,
+            ..BacktestConfig::default()
+        };
+        let backtester = Backtester::new(config).unwrap();
+        let strategy = Strategy::ThresholdCrossing { threshold: 0.75 };
+
+        let result = backtester
+            .run(strategy.build(), &path(&[0.5, 0.55, 0.6, 0.7, 0.8]))
+            .unwrap();
+
+        assert_eq!(result.outcome, Some(SettlementOutcome::Resolved));
+        assert_eq!(result.trades[0].position_type, PositionType::Long);
+        assert!(result.total_return > 0.0);
+        // One point per traded update plus the settlement point
+        assert_eq!(result.equity_curve.len(), 5);
+        assert_eq!(result.equity_curve.last().unwrap().position, 0.0);
+    }
+
+    /// Stateful strategy that buys once and then holds
+    #[derive(Debug, Clone, Default)]
+    struct BuyOnce {
+        filled: f64,
+    }
+
+    impl TradingStrategy for BuyOnce {
+        fn name(&self) -> String {
+            "Buy Once".to_string()
+        }
+
+        fn on_tick(&mut self, _observation: &MarketObservation) -> StrategyAction {
+            if self.filled > 0.0 {
+                StrategyAction::Hold
+            } else {
+                StrategyAction::Order {
+                    side: PositionType::Long,
+                    size: 100.0,
+                }
+            }
+        }
+
+        fn on_fill(&mut self, trade: &Trade) {
+            self.filled += trade.size;
+        }
+
+        fn clone_box(&self) -> Box<dyn TradingStrategy> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_custom_stateful_strategy() {
+        let backtester = Backtester::new(BacktestConfig::default()).unwrap();
+
+        let result = backtester
+            .run(Box::new(BuyOnce::default()), &path(&[0.4, 0.5, 0.6]))
+            .unwrap();
+
+        // Held to expiry, so the long position pays nothing
+        assert_eq!(result.strategy_name, "Buy Once");
+        assert_eq!(result.num_trades, 1);
+        assert_eq!(result.outcome, Some(SettlementOutcome::Expired));
+        assert!((result.total_return + 0.04).abs() < 1e-9);
+    }
+
+    /// Strategy that orders far more YES contracts than it can pay for
+    #[derive(Debug, Clone)]
+    struct AllIn;
+
+    impl TradingStrategy for AllIn {
+        fn name(&self) -> String {
+            "All In".to_string()
+        }
+
+        fn on_tick(&mut self, _observation: &MarketObservation) -> StrategyAction {
+            StrategyAction::Order {
+                side: PositionType::Long,
+                size: 1e6,
+            }
+        }
+
+        fn clone_box(&self) -> Box<dyn TradingStrategy> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_orders_are_capped_at_capital() {
+        let backtester = Backtester::new(BacktestConfig {
+            slippage_bps: 50,
+            fees: FeeConfig {
+                taker_fee_bps: 30,
+                ..FeeConfig::default()
+            },
+            ..BacktestConfig::default()
+        })
+        .unwrap();
+
+        let result = backtester
+            .run(Box::new(AllIn), &path(&[0.4, 0.5, 0.45]))
+            .unwrap();
+
+        // Everything is spent on the first update and nothing is left after
+        assert_eq!(result.num_trades, 1);
+        assert!(result.trades[0].size < 2500.0);
+        assert!(result.equity_curve[0].equity > 0.0);
+        // Expired worthless, leaving capital at zero up to rounding
+        assert!(result.equity_curve.iter().all(|p| p.equity >= -1e-9));
+        assert!((result.total_return + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fees_and_slippage_reduce_returns() {
+        let values = [0.5, 0.6, 0.4, 0.6, 0.4, 0.6, 0.4, 0.5];
+        let strategy = Strategy::MeanReversion {
+            mean: 0.5,
+            deviation: 0.05,
+        };
+
+        let free = Backtester::new(BacktestConfig::default())
+            .unwrap()
+            .run(strategy.build(), &path(&values))
+            .unwrap();
+        let costly = Backtester::new(BacktestConfig {
+            slippage_bps: 50,
+            fees: FeeConfig {
+                taker_fee_bps: 30,
+                ..FeeConfig::default()
+            },
+            ..BacktestConfig::default()
+        })
+        .unwrap()
+        .run(strategy.build(), &path(&values))
+        .unwrap();
+
+        assert_eq!(free.num_trades, costly.num_trades);
+        assert!(free.total_fees == 0.0 && costly.total_fees > 0.0);
+        assert!(costly.total_return < free.total_return);
+        assert!((costly.gross_return - costly.total_return - costly.total_fees).abs() < 1e-12);
+    }
+}
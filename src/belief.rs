@@ -0,0 +1,503 @@
+//! Stochastic processes driving the latent belief
+//!
+//! Each oracle update advances the belief by one step of a [`BeliefProcess`].
+//! Scenario targets replace the long-run level a process reverts to.
+
+use crate::error::{Result, SimulatorError};
+use crate::rng::SimRng;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Smallest distance from 0 and 1 kept when mapping beliefs to logit space
+const LOGIT_EPSILON: f64 = 1e-6;
+
+/// A stochastic process for the latent belief
+///
+/// Processes are stateful and advanced once per oracle update.
+pub trait BeliefProcess: Debug + Send + Sync {
+    /// Short process name used in reports
+    fn name(&self) -> &'static str;
+
+    /// Advance from `current`, reverting toward `target` when one is set
+    ///
+    /// The result may fall outside `[0, 1]`; the oracle clamps it.
+    fn step(&mut self, current: f64, target: Option<f64>, rng: &mut SimRng) -> Result<f64>;
+
+    /// Clone into a boxed trait object
+    fn clone_box(&self) -> Box<dyn BeliefProcess>;
+}
+
+impl Clone for Box<dyn BeliefProcess> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Draw from `N(mean, std_dev)`
+fn gaussian(mean: f64, std_dev: f64, rng: &mut SimRng) -> Result<f64> {
+    let normal =
+        Normal::new(mean, std_dev).map_err(|e| SimulatorError::OracleError(e.to_string()))?;
+    Ok(normal.sample(rng))
+}
+
+/// Original blend of drift, Gaussian noise and reversion to 0.5
+#[derive(Debug, Clone)]
+pub struct LegacyProcess {
+    noise_level: f64,
+    drift_rate: f64,
+    mean_reversion: f64,
+}
+
+impl LegacyProcess {
+    /// Create the process from the oracle's noise, drift and reversion settings
+    pub fn new(noise_level: f64, drift_rate: f64, mean_reversion: f64) -> Self {
+        LegacyProcess {
+            noise_level,
+            drift_rate,
+            mean_reversion,
+        }
+    }
+}
+
+impl BeliefProcess for LegacyProcess {
+    fn name(&self) -> &'static str {
+        "legacy"
+    }
+
+    fn step(&mut self, current: f64, target: Option<f64>, rng: &mut SimRng) -> Result<f64> {
+        let mut next = current;
+        next += match target {
+            Some(target) => (target - next) * self.drift_rate,
+            None if self.drift_rate > 0.0 => rng.gen_range(-self.drift_rate..self.drift_rate),
+            None => 0.0,
+        };
+        next += gaussian(0.0, self.noise_level, rng)?;
+        next += (0.5 - next) * self.mean_reversion;
+        Ok(next)
+    }
+
+    fn clone_box(&self) -> Box<dyn BeliefProcess> {
+        Box::new(self.clone())
+    }
+}
+
+/// Ornstein-Uhlenbeck process with optional Merton-style jumps
+///
+/// Each update reverts a fraction `reversion` of the gap to the long-run
+/// mean, adds Gaussian noise, and with probability `jump_probability` adds
+/// a normally distributed jump.
+#[derive(Debug, Clone)]
+pub struct OrnsteinUhlenbeck {
+    mean: f64,
+    reversion: f64,
+    volatility: f64,
+    jumps: Option<Jumps>,
+}
+
+/// Arrival probability and size distribution of jumps
+#[derive(Debug, Clone, Copy)]
+struct Jumps {
+    probability: f64,
+    mean: f64,
+    std_dev: f64,
+}
+
+impl OrnsteinUhlenbeck {
+    /// Create a process without jumps
+    pub fn new(mean: f64, reversion: f64, volatility: f64) -> Self {
+        OrnsteinUhlenbeck {
+            mean,
+            reversion,
+            volatility,
+            jumps: None,
+        }
+    }
+
+    /// Add jumps of `N(jump_mean, jump_std)` arriving with `probability` per update
+    pub fn with_jumps(mut self, probability: f64, jump_mean: f64, jump_std: f64) -> Self {
+        self.jumps = Some(Jumps {
+            probability,
+            mean: jump_mean,
+            std_dev: jump_std,
+        });
+        self
+    }
+}
+
+impl BeliefProcess for OrnsteinUhlenbeck {
+    fn name(&self) -> &'static str {
+        match self.jumps {
+            Some(_) => "jump_diffusion",
+            None => "ornstein_uhlenbeck",
+        }
+    }
+
+    fn step(&mut self, current: f64, target: Option<f64>, rng: &mut SimRng) -> Result<f64> {
+        let mean = target.unwrap_or(self.mean);
+        let noise = gaussian(0.0, self.volatility, rng)?;
+        let mut next = current + self.reversion * (mean - current) + noise;
+        if let Some(jumps) = self.jumps {
+            if rng.gen_bool(jumps.probability) {
+                next += gaussian(jumps.mean, jumps.std_dev, rng)?;
+            }
+        }
+        Ok(next)
+    }
+
+    fn clone_box(&self) -> Box<dyn BeliefProcess> {
+        Box::new(self.clone())
+    }
+}
+
+/// Mean-reverting process whose volatility follows a Markov chain of regimes
+///
+/// On each update the process leaves its regime with probability
+/// `switch_probability`, moving to one of the other regimes uniformly.
+#[derive(Debug, Clone)]
+pub struct RegimeSwitching {
+    mean: f64,
+    reversion: f64,
+    volatilities: Vec<f64>,
+    switch_probability: f64,
+    regime: usize,
+}
+
+impl RegimeSwitching {
+    /// Create a process starting in the first of the given volatility regimes
+    pub fn new(mean: f64, reversion: f64, volatilities: Vec<f64>, switch_probability: f64) -> Self {
+        RegimeSwitching {
+            mean,
+            reversion,
+            volatilities,
+            switch_probability,
+            regime: 0,
+        }
+    }
+
+    /// Index of the current volatility regime
+    pub fn regime(&self) -> usize {
+        self.regime
+    }
+}
+
+impl BeliefProcess for RegimeSwitching {
+    fn name(&self) -> &'static str {
+        "regime_switching"
+    }
+
+    fn step(&mut self, current: f64, target: Option<f64>, rng: &mut SimRng) -> Result<f64> {
+        let count = self.volatilities.len();
+        if count == 0 {
+            return Err(SimulatorError::OracleError(
+                "Regime switching needs at least one regime".to_string(),
+            ));
+        }
+        if count > 1 && rng.gen_bool(self.switch_probability) {
+            self.regime = (self.regime + rng.gen_range(1..count)) % count;
+        }
+
+        let mean = target.unwrap_or(self.mean);
+        let noise = gaussian(0.0, self.volatilities[self.regime], rng)?;
+        Ok(current + self.reversion * (mean - current) + noise)
+    }
+
+    fn clone_box(&self) -> Box<dyn BeliefProcess> {
+        Box::new(self.clone())
+    }
+}
+
+/// Random walk on the log-odds of the belief, which never leaves `(0, 1)`
+///
+/// With a scenario target the log-odds also revert toward the target's
+/// log-odds at rate `reversion`.
+#[derive(Debug, Clone)]
+pub struct LogitWalk {
+    drift: f64,
+    volatility: f64,
+    reversion: f64,
+}
+
+impl LogitWalk {
+    /// Create a walk with per-update log-odds drift and volatility
+    pub fn new(drift: f64, volatility: f64, reversion: f64) -> Self {
+        LogitWalk {
+            drift,
+            volatility,
+            reversion,
+        }
+    }
+}
+
+/// Log-odds of a belief
+pub fn logit(p: f64) -> f64 {
+    let p = p.clamp(LOGIT_EPSILON, 1.0 - LOGIT_EPSILON);
+    (p / (1.0 - p)).ln()
+}
+
+/// Belief with the given log-odds
+pub fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+impl BeliefProcess for LogitWalk {
+    fn name(&self) -> &'static str {
+        "logit_walk"
+    }
+
+    fn step(&mut self, current: f64, target: Option<f64>, rng: &mut SimRng) -> Result<f64> {
+        let x = logit(current);
+        let pull = target.map_or(0.0, |target| self.reversion * (logit(target) - x));
+        let noise = gaussian(0.0, self.volatility, rng)?;
+        let next = logistic(x + self.drift + pull + noise);
+        Ok(next.clamp(LOGIT_EPSILON, 1.0 - LOGIT_EPSILON))
+    }
+
+    fn clone_box(&self) -> Box<dyn BeliefProcess> {
+        Box::new(self.clone())
+    }
+}
+
+/// Serializable selection of a belief process
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum BeliefProcessConfig {
+    /// Original blend driven by the oracle's noise, drift and reversion settings
+    #[default]
+    Legacy,
+    /// Ornstein-Uhlenbeck reversion to `mean`
+    OrnsteinUhlenbeck {
+        mean: f64,
+        reversion: f64,
+        volatility: f64,
+    },
+    /// Ornstein-Uhlenbeck process with normally distributed jumps
+    JumpDiffusion {
+        mean: f64,
+        reversion: f64,
+        volatility: f64,
+        jump_probability: f64,
+        jump_mean: f64,
+        jump_std: f64,
+    },
+    /// Reversion to `mean` with Markov-switching volatility regimes
+    RegimeSwitching {
+        mean: f64,
+        reversion: f64,
+        volatilities: Vec<f64>,
+        switch_probability: f64,
+    },
+    /// Random walk in log-odds space
+    LogitWalk {
+        drift: f64,
+        volatility: f64,
+        reversion: f64,
+    },
+}
+
+impl BeliefProcessConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: &str| Err(SimulatorError::InvalidConfig(message.to_string()));
+        let unit = |value: f64| (0.0..=1.0).contains(&value);
+        let non_negative = |value: f64| value.is_finite() && value >= 0.0;
+
+        match self {
+            BeliefProcessConfig::Legacy => Ok(()),
+            BeliefProcessConfig::OrnsteinUhlenbeck {
+                mean,
+                reversion,
+                volatility,
+            } => {
+                if !unit(*mean) || !unit(*reversion) || !non_negative(*volatility) {
+                    return invalid("OU mean and reversion must be in [0, 1] and volatility >= 0");
+                }
+                Ok(())
+            }
+            BeliefProcessConfig::JumpDiffusion {
+                mean,
+                reversion,
+                volatility,
+                jump_probability,
+                jump_mean,
+                jump_std,
+            } => {
+                if !unit(*mean) || !unit(*reversion) || !non_negative(*volatility) {
+                    return invalid("OU mean and reversion must be in [0, 1] and volatility >= 0");
+                }
+                if !unit(*jump_probability) || !jump_mean.is_finite() || !non_negative(*jump_std) {
+                    return invalid("Jump probability must be in [0, 1] and jump std >= 0");
+                }
+                Ok(())
+            }
+            BeliefProcessConfig::RegimeSwitching {
+                mean,
+                reversion,
+                volatilities,
+                switch_probability,
+            } => {
+                if !unit(*mean) || !unit(*reversion) || !unit(*switch_probability) {
+                    return invalid(
+                        "Regime mean, reversion and switch probability must be in [0, 1]",
+                    );
+                }
+                if volatilities.is_empty() || !volatilities.iter().all(|&v| non_negative(v)) {
+                    return invalid("Regime switching needs at least one volatility >= 0");
+                }
+                Ok(())
+            }
+            BeliefProcessConfig::LogitWalk {
+                drift,
+                volatility,
+                reversion,
+            } => {
+                if !drift.is_finite() || !non_negative(*volatility) || !unit(*reversion) {
+                    return invalid("Logit walk volatility must be >= 0 and reversion in [0, 1]");
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The same process with every volatility parameter scaled by `multiplier`
+    ///
+    /// `Legacy` is unchanged; its volatility is the oracle noise level.
+    pub fn with_volatility_scaled(&self, multiplier: f64) -> Self {
+        let mut scaled = self.clone();
+        match &mut scaled {
+            BeliefProcessConfig::Legacy => {}
+            BeliefProcessConfig::OrnsteinUhlenbeck { volatility, .. }
+            | BeliefProcessConfig::LogitWalk { volatility, .. } => *volatility *= multiplier,
+            BeliefProcessConfig::JumpDiffusion {
+                volatility,
+                jump_std,
+                ..
+            } => {
+                *volatility *= multiplier;
+                *jump_std *= multiplier;
+            }
+            BeliefProcessConfig::RegimeSwitching { volatilities, .. } => {
+                volatilities.iter_mut().for_each(|v| *v *= multiplier);
+            }
+        }
+        scaled
+    }
+
+    /// Build the process; `Legacy` uses the given noise, drift and reversion
+    pub fn build(
+        &self,
+        noise_level: f64,
+        drift_rate: f64,
+        mean_reversion: f64,
+    ) -> Box<dyn BeliefProcess> {
+        match self {
+            BeliefProcessConfig::Legacy => {
+                Box::new(LegacyProcess::new(noise_level, drift_rate, mean_reversion))
+            }
+            BeliefProcessConfig::OrnsteinUhlenbeck {
+                mean,
+                reversion,
+                volatility,
+            } => Box::new(OrnsteinUhlenbeck::new(*mean, *reversion, *volatility)),
+            BeliefProcessConfig::JumpDiffusion {
+                mean,
+                reversion,
+                volatility,
+                jump_probability,
+                jump_mean,
+                jump_std,
+            } => Box::new(
+                OrnsteinUhlenbeck::new(*mean, *reversion, *volatility).with_jumps(
+                    *jump_probability,
+                    *jump_mean,
+                    *jump_std,
+                ),
+            ),
+            BeliefProcessConfig::RegimeSwitching {
+                mean,
+                reversion,
+                volatilities,
+                switch_probability,
+            } => Box::new(RegimeSwitching::new(
+                *mean,
+                *reversion,
+                volatilities.clone(),
+                *switch_probability,
+            )),
+            BeliefProcessConfig::LogitWalk {
+                drift,
+                volatility,
+                reversion,
+            } => Box::new(LogitWalk::new(*drift, *volatility, *reversion)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::{RngStreams, ORACLE_STREAM};
+
+    fn path(process: &mut dyn BeliefProcess, start: f64, steps: usize) -> Vec<f64> {
+        let mut rng = RngStreams::new(5).stream(ORACLE_STREAM);
+        let mut value = start;
+        (0..steps)
+            .map(|_| {
+                value = process.step(value, None, &mut rng).unwrap().clamp(0.0, 1.0);
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ou_reverts_to_configured_mean() {
+        let mut process = OrnsteinUhlenbeck::new(0.3, 0.1, 0.02);
+        let values = path(&mut process, 0.9, 5000);
+
+        let tail = &values[1000..];
+        let mean = tail.iter().sum::<f64>() / tail.len() as f64;
+        assert!((mean - 0.3).abs() < 0.01);
+        assert_eq!(process.name(), "ornstein_uhlenbeck");
+    }
+
+    #[test]
+    fn test_logit_walk_stays_inside_unit_interval() {
+        let mut process = LogitWalk::new(0.0, 1.0, 0.0);
+        let values = path(&mut process, 0.5, 5000);
+
+        assert!(values.iter().all(|&v| v > 0.0 && v < 1.0));
+        // The legacy blend piles mass on the bounds at high volatility
+        let mut legacy = LegacyProcess::new(0.5, 0.0, 0.0);
+        let clamped = path(&mut legacy, 0.5, 5000);
+        assert!(clamped.iter().filter(|&&v| v == 0.0 || v == 1.0).count() > 1000);
+    }
+
+    #[test]
+    fn test_jumps_and_regimes() {
+        let config = BeliefProcessConfig::JumpDiffusion {
+            mean: 0.5,
+            reversion: 0.1,
+            volatility: 0.0,
+            jump_probability: 0.05,
+            jump_mean: 0.0,
+            jump_std: 0.2,
+        };
+        assert!(config.validate().is_ok());
+        let mut jumpy = config.build(0.0, 0.0, 0.0);
+        let values = path(jumpy.as_mut(), 0.5, 2000);
+        let jumps = values
+            .windows(2)
+            .filter(|w| (w[1] - w[0]).abs() > 0.05)
+            .count();
+        assert!((50..=150).contains(&jumps));
+
+        let mut regimes = RegimeSwitching::new(0.5, 0.1, vec![0.01, 0.2], 0.05);
+        let mut rng = RngStreams::new(5).stream(ORACLE_STREAM);
+        let mut visited = [false; 2];
+        for _ in 0..500 {
+            regimes.step(0.5, None, &mut rng).unwrap();
+            visited[regimes.regime()] = true;
+        }
+        assert_eq!(visited, [true, true]);
+    }
+}
@@ -0,0 +1,513 @@
+//! Calibration of belief processes to historical BSI series
+//!
+//! The series is resampled to the oracle update frequency and each model is
+//! fitted to its one-step transitions. Mean-reverting models are fitted by
+//! least squares on the AR(1) form `x' = a + b x + e`, which is the
+//! conditional maximum likelihood estimate under Gaussian noise. Jumps are
+//! separated from diffusion by a robust threshold on the residuals, and their
+//! moments are estimated from the residuals beyond it.
+
+use crate::belief::{logistic, logit, BeliefProcessConfig};
+use crate::error::{Result, SimulatorError};
+use crate::history::BsiSeries;
+use crate::oracle::OracleConfig;
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+use std::f64::consts::PI;
+
+/// Fewest transitions a series must have to be calibrated
+pub const MIN_TRANSITIONS: usize = 10;
+
+/// Residuals further than this many robust standard deviations from the median are jumps
+const JUMP_THRESHOLD: f64 = 3.0;
+
+/// Largest lag used in the Ljung-Box test
+const LJUNG_BOX_LAGS: usize = 10;
+
+/// Belief process fitted by calibration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CalibrationModel {
+    /// Ornstein-Uhlenbeck reversion to a fitted mean
+    OrnsteinUhlenbeck,
+    /// Ornstein-Uhlenbeck process with normally distributed jumps
+    JumpDiffusion,
+    /// Random walk in log-odds space
+    LogitWalk,
+}
+
+impl CalibrationModel {
+    /// All calibratable models
+    pub fn all() -> [CalibrationModel; 3] {
+        [
+            CalibrationModel::OrnsteinUhlenbeck,
+            CalibrationModel::JumpDiffusion,
+            CalibrationModel::LogitWalk,
+        ]
+    }
+}
+
+/// Diagnostics of the one-step residuals of a fitted model
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResidualDiagnostics {
+    /// Mean residual
+    pub mean: f64,
+    /// Residual standard deviation
+    pub std_dev: f64,
+    /// Residual skewness
+    pub skewness: f64,
+    /// Residual excess kurtosis
+    pub excess_kurtosis: f64,
+    /// Lag-1 autocorrelation of residuals
+    pub autocorrelation: f64,
+    /// Ljung-Box statistic for residual autocorrelation
+    pub ljung_box: f64,
+    /// Ljung-Box p-value; small values indicate remaining autocorrelation
+    pub ljung_box_p_value: f64,
+    /// Jarque-Bera statistic for residual normality
+    pub jarque_bera: f64,
+    /// Jarque-Bera p-value; small values indicate non-normal residuals
+    pub jarque_bera_p_value: f64,
+}
+
+impl ResidualDiagnostics {
+    fn new(residuals: &[f64]) -> Self {
+        let n = residuals.len() as f64;
+        let mean = residuals.iter().sum::<f64>() / n;
+        let moment = |k: i32| residuals.iter().map(|r| (r - mean).powi(k)).sum::<f64>() / n;
+        let variance = moment(2);
+        let (skewness, excess_kurtosis) = if variance > 0.0 {
+            (
+                moment(3) / variance.powf(1.5),
+                moment(4) / variance.powi(2) - 3.0,
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        let autocorrelation = |lag: usize| {
+            if variance <= 0.0 {
+                return 0.0;
+            }
+            let covariance = residuals
+                .iter()
+                .zip(&residuals[lag..])
+                .map(|(a, b)| (a - mean) * (b - mean))
+                .sum::<f64>()
+                / n;
+            covariance / variance
+        };
+        let lags = LJUNG_BOX_LAGS.min(residuals.len() / 4).max(1);
+        let ljung_box = n
+            * (n + 2.0)
+            * (1..=lags)
+                .map(|k| autocorrelation(k).powi(2) / (n - k as f64))
+                .sum::<f64>();
+        let jarque_bera = n / 6.0 * (skewness.powi(2) + excess_kurtosis.powi(2) / 4.0);
+
+        ResidualDiagnostics {
+            mean,
+            std_dev: variance.sqrt(),
+            skewness,
+            excess_kurtosis,
+            autocorrelation: autocorrelation(1),
+            ljung_box,
+            ljung_box_p_value: chi_squared_p_value(ljung_box, lags as f64),
+            jarque_bera,
+            jarque_bera_p_value: chi_squared_p_value(jarque_bera, 2.0),
+        }
+    }
+}
+
+fn chi_squared_p_value(statistic: f64, freedom: f64) -> f64 {
+    ChiSquared::new(freedom).map_or(f64::NAN, |chi| 1.0 - chi.cdf(statistic))
+}
+
+fn normal_log_density(x: f64, mean: f64, std_dev: f64) -> f64 {
+    -0.5 * ((x - mean) / std_dev).powi(2) - std_dev.ln() - 0.5 * (2.0 * PI).ln()
+}
+
+/// A belief process fitted to a historical series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    /// Model fitted
+    pub model: CalibrationModel,
+    /// Fitted process parameters
+    pub process: BeliefProcessConfig,
+    /// Update frequency the series was resampled to, in seconds
+    pub update_frequency: u32,
+    /// Number of one-step transitions fitted
+    pub transitions: usize,
+    /// Log-likelihood of the transitions in BSI space
+    pub log_likelihood: f64,
+    /// Akaike information criterion
+    pub aic: f64,
+    /// Bayesian information criterion
+    pub bic: f64,
+    /// Share of the variance of next values explained by one-step predictions
+    pub r_squared: f64,
+    /// Residual diagnostics, in log-odds space for the logit walk
+    pub residuals: ResidualDiagnostics,
+}
+
+impl Calibration {
+    /// Fit `model` to `series` resampled to `update_frequency` seconds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the update frequency is zero or the resampled
+    /// series has fewer than [`MIN_TRANSITIONS`] transitions
+    pub fn fit(series: &BsiSeries, model: CalibrationModel, update_frequency: u32) -> Result<Self> {
+        if update_frequency == 0 {
+            return Err(SimulatorError::InvalidConfig(
+                "Update frequency must be greater than 0".to_string(),
+            ));
+        }
+        let resampled = series.resample(Duration::seconds(update_frequency as i64))?;
+        let values: Vec<f64> = resampled.records().iter().map(|r| r.value).collect();
+        if values.len() <= MIN_TRANSITIONS {
+            return Err(SimulatorError::DataError(format!(
+                "calibration needs at least {} transitions, found {}",
+                MIN_TRANSITIONS,
+                values.len().saturating_sub(1)
+            )));
+        }
+
+        let fit = match model {
+            CalibrationModel::OrnsteinUhlenbeck => fit_mean_reverting(&values, false),
+            CalibrationModel::JumpDiffusion => fit_mean_reverting(&values, true),
+            CalibrationModel::LogitWalk => fit_logit_walk(&values),
+        };
+
+        let n = (values.len() - 1) as f64;
+        let next = &values[1..];
+        let mean_next = next.iter().sum::<f64>() / n;
+        let total = next.iter().map(|x| (x - mean_next).powi(2)).sum::<f64>();
+        let unexplained = next
+            .iter()
+            .zip(&fit.predictions)
+            .map(|(x, prediction)| (x - prediction).powi(2))
+            .sum::<f64>();
+        let r_squared = if total > 0.0 {
+            1.0 - unexplained / total
+        } else {
+            1.0
+        };
+
+        Ok(Calibration {
+            model,
+            process: fit.process,
+            update_frequency,
+            transitions: values.len() - 1,
+            log_likelihood: fit.log_likelihood,
+            aic: 2.0 * fit.parameters - 2.0 * fit.log_likelihood,
+            bic: fit.parameters * n.ln() - 2.0 * fit.log_likelihood,
+            r_squared,
+            residuals: ResidualDiagnostics::new(&fit.residuals),
+        })
+    }
+
+    /// Fit every model and return the one with the lowest AIC
+    pub fn fit_best(series: &BsiSeries, update_frequency: u32) -> Result<Self> {
+        let mut best: Option<Calibration> = None;
+        for model in CalibrationModel::all() {
+            let fit = Self::fit(series, model, update_frequency)?;
+            match &best {
+                Some(best) if best.aic <= fit.aic => {}
+                _ => best = Some(fit),
+            }
+        }
+        best.ok_or_else(|| SimulatorError::DataError("no calibration models".to_string()))
+    }
+
+    /// Oracle configuration for the fitted process
+    ///
+    /// The legacy noise, drift and mean reversion settings are filled from the
+    /// fit as well, and observations are noiseless. The fitted parameters are
+    /// per update of [`Calibration::update_frequency`] seconds, but a
+    /// simulation always steps the oracle every `update_frequency_secs`, so
+    /// set that to the fitted frequency or the process runs at the wrong
+    /// speed.
+    pub fn oracle_config(&self) -> OracleConfig {
+        let (noise_level, drift_rate, mean_reversion) = match &self.process {
+            BeliefProcessConfig::OrnsteinUhlenbeck {
+                reversion,
+                volatility,
+                ..
+            }
+            | BeliefProcessConfig::JumpDiffusion {
+                reversion,
+                volatility,
+                ..
+            } => (*volatility, 0.0, *reversion),
+            BeliefProcessConfig::LogitWalk { drift, .. } => {
+                (self.residuals.std_dev, drift.abs(), 0.0)
+            }
+            _ => (0.0, 0.0, 0.0),
+        };
+
+        OracleConfig {
+            update_frequency: self.update_frequency,
+            noise_level: noise_level.min(1.0),
+            drift_rate: drift_rate.min(1.0),
+            mean_reversion,
+            process: self.process.clone(),
+            ..OracleConfig::default()
+        }
+    }
+}
+
+/// Parameters, one-step predictions and residuals of a fitted model
+struct Fit {
+    process: BeliefProcessConfig,
+    predictions: Vec<f64>,
+    residuals: Vec<f64>,
+    log_likelihood: f64,
+    parameters: f64,
+}
+
+/// Least-squares AR(1) fit, optionally splitting residuals into diffusion and jumps
+fn fit_mean_reverting(values: &[f64], jumps: bool) -> Fit {
+    let (x, y) = (&values[..values.len() - 1], &values[1..]);
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let sxx = x.iter().map(|v| (v - mean_x).powi(2)).sum::<f64>();
+    let sxy = x
+        .iter()
+        .zip(y)
+        .map(|(a, b)| (a - mean_x) * (b - mean_y))
+        .sum::<f64>();
+    let slope = if sxx > 0.0 { sxy / sxx } else { 1.0 };
+    let intercept = mean_y - slope * mean_x;
+
+    // Reversion outside [0, 1] is not a stationary OU process; clamp and
+    // fall back to the sample mean when there is no pull to estimate it from
+    let reversion = (1.0 - slope).clamp(0.0, 1.0);
+    let mean = if reversion > 1e-9 {
+        (intercept / (1.0 - slope)).clamp(0.0, 1.0)
+    } else {
+        mean_x
+    };
+
+    let predictions: Vec<f64> = x.iter().map(|v| v + reversion * (mean - v)).collect();
+    let residuals: Vec<f64> = y.iter().zip(&predictions).map(|(v, p)| v - p).collect();
+    let volatility = std_dev(&residuals);
+
+    if !jumps {
+        let sigma = volatility.max(f64::EPSILON);
+        return Fit {
+            process: BeliefProcessConfig::OrnsteinUhlenbeck {
+                mean,
+                reversion,
+                volatility,
+            },
+            log_likelihood: residuals
+                .iter()
+                .map(|r| normal_log_density(*r, 0.0, sigma))
+                .sum(),
+            predictions,
+            residuals,
+            parameters: 3.0,
+        };
+    }
+
+    // Robust scale from the median absolute deviation
+    let center = median(&residuals);
+    let deviations: Vec<f64> = residuals.iter().map(|r| (r - center).abs()).collect();
+    let robust_sigma = 1.4826 * median(&deviations);
+    let (jump_residuals, diffusion): (Vec<f64>, Vec<f64>) = residuals
+        .iter()
+        .partition(|r| (**r - center).abs() > JUMP_THRESHOLD * robust_sigma);
+
+    let jump_probability = jump_residuals.len() as f64 / n;
+    let jump_mean = mean_of(&jump_residuals);
+    let jump_std = std_dev(&jump_residuals);
+    let volatility = std_dev(&diffusion);
+
+    let sigma = volatility.max(f64::EPSILON);
+    let jump_sigma = (volatility.powi(2) + jump_std.powi(2))
+        .sqrt()
+        .max(f64::EPSILON);
+    let log_likelihood = residuals
+        .iter()
+        .map(|r| {
+            let diffusion = (1.0 - jump_probability) * normal_log_density(*r, 0.0, sigma).exp();
+            let jump = jump_probability * normal_log_density(*r, jump_mean, jump_sigma).exp();
+            (diffusion + jump).max(f64::MIN_POSITIVE).ln()
+        })
+        .sum();
+
+    Fit {
+        process: BeliefProcessConfig::JumpDiffusion {
+            mean,
+            reversion,
+            volatility,
+            jump_probability,
+            jump_mean,
+            jump_std,
+        },
+        predictions: predictions
+            .iter()
+            .map(|p| p + jump_probability * jump_mean)
+            .collect(),
+        residuals,
+        log_likelihood,
+        parameters: 6.0,
+    }
+}
+
+/// Moment fit of log-odds increments
+///
+/// The likelihood includes the Jacobian of the logit transform so that it
+/// is comparable with the models fitted in BSI space.
+fn fit_logit_walk(values: &[f64]) -> Fit {
+    let increments: Vec<f64> = values
+        .windows(2)
+        .map(|w| logit(w[1]) - logit(w[0]))
+        .collect();
+    let drift = mean_of(&increments);
+    let volatility = std_dev(&increments);
+    let sigma = volatility.max(f64::EPSILON);
+
+    let log_likelihood = increments
+        .iter()
+        .zip(&values[1..])
+        .map(|(d, x)| {
+            // Bound away from 0 and 1 as in the transform
+            let x = logistic(logit(*x));
+            normal_log_density(*d, drift, sigma) - (x * (1.0 - x)).ln()
+        })
+        .sum();
+
+    Fit {
+        process: BeliefProcessConfig::LogitWalk {
+            drift,
+            volatility,
+            reversion: 0.0,
+        },
+        predictions: values[..values.len() - 1]
+            .iter()
+            .map(|x| logistic(logit(*x) + drift))
+            .collect(),
+        residuals: increments.iter().map(|d| d - drift).collect(),
+        log_likelihood,
+        parameters: 2.0,
+    }
+}
+
+fn mean_of(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn std_dev(values: &[f64]) -> f64 {
+    let mean = mean_of(values);
+    mean_of(
+        &values
+            .iter()
+            .map(|v| (v - mean).powi(2))
+            .collect::<Vec<_>>(),
+    )
+    .sqrt()
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let n = sorted.len();
+    (sorted[(n - 1) / 2] + sorted[n / 2]) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+    use crate::history::BsiRecord;
+    use crate::rng::{RngStreams, ORACLE_STREAM};
+
+    fn simulate(process: BeliefProcessConfig, start: f64, steps: usize) -> BsiSeries {
+        let mut rng = RngStreams::new(13).stream(ORACLE_STREAM);
+        let mut process = process.build(0.0, 0.0, 0.0);
+        let mut value = start;
+        let records = (0..steps)
+            .map(|i| {
+                if i > 0 {
+                    value = process.step(value, None, &mut rng).unwrap().clamp(0.0, 1.0);
+                }
+                BsiRecord {
+                    timestamp: default_start_time() + Duration::minutes(5 * i as i64),
+                    value,
+                    source: None,
+                    confidence: None,
+                }
+            })
+            .collect();
+        BsiSeries::new(records).unwrap()
+    }
+
+    #[test]
+    fn test_ou_parameters_are_recovered() {
+        let truth = BeliefProcessConfig::OrnsteinUhlenbeck {
+            mean: 0.4,
+            reversion: 0.05,
+            volatility: 0.01,
+        };
+        let series = simulate(truth, 0.6, 5000);
+
+        let fit = Calibration::fit(&series, CalibrationModel::OrnsteinUhlenbeck, 300).unwrap();
+
+        match fit.process {
+            BeliefProcessConfig::OrnsteinUhlenbeck {
+                mean,
+                reversion,
+                volatility,
+            } => {
+                assert!((mean - 0.4).abs() < 0.01);
+                assert!((reversion - 0.05).abs() < 0.01);
+                assert!((volatility - 0.01).abs() < 0.001);
+            }
+            process => panic!("unexpected process {:?}", process),
+        }
+        assert!(fit.residuals.ljung_box_p_value > 0.01);
+        let oracle = fit.oracle_config();
+        assert!(oracle.validate().is_ok());
+        assert_eq!(oracle.process, fit.process);
+    }
+
+    #[test]
+    fn test_jumps_are_detected_and_preferred() {
+        let truth = BeliefProcessConfig::JumpDiffusion {
+            mean: 0.5,
+            reversion: 0.05,
+            volatility: 0.005,
+            jump_probability: 0.02,
+            jump_mean: 0.0,
+            jump_std: 0.08,
+        };
+        let series = simulate(truth, 0.5, 5000);
+
+        let best = Calibration::fit_best(&series, 300).unwrap();
+
+        assert_eq!(best.model, CalibrationModel::JumpDiffusion);
+        match best.process {
+            BeliefProcessConfig::JumpDiffusion {
+                volatility,
+                jump_probability,
+                ..
+            } => {
+                assert!((volatility - 0.005).abs() < 0.001);
+                assert!((jump_probability - 0.02).abs() < 0.01);
+            }
+            process => panic!("unexpected process {:?}", process),
+        }
+    }
+
+    #[test]
+    fn test_short_series_is_rejected() {
+        let series = simulate(BeliefProcessConfig::Legacy, 0.5, 5);
+        assert!(Calibration::fit(&series, CalibrationModel::LogitWalk, 300).is_err());
+    }
+}
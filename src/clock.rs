@@ -0,0 +1,105 @@
+//! Virtual simulation clock
+
+use crate::types::TimeInterval;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+/// Default start instant for simulations (2024-01-01T00:00:00Z)
+pub fn default_start_time() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+}
+
+/// Virtual clock driving a simulation run
+///
+/// Simulated time only moves when [`SimClock::advance`] is called, so runs
+/// never depend on the wall clock unless pacing is explicitly enabled.
+#[derive(Debug, Clone)]
+pub struct SimClock {
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+    tick: Duration,
+    pacing: Option<std::time::Duration>,
+}
+
+impl SimClock {
+    /// Create a clock starting at `start` that advances by `tick`
+    pub fn new(start: DateTime<Utc>, tick: Duration) -> Self {
+        SimClock {
+            start,
+            now: start,
+            tick,
+            pacing: None,
+        }
+    }
+
+    /// Wait `per_tick` of wall-clock time on every advance
+    pub fn with_pacing(mut self, per_tick: std::time::Duration) -> Self {
+        self.pacing = Some(per_tick);
+        self
+    }
+
+    /// Get the start instant
+    pub fn start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    /// Get the current simulated instant
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    /// Get the tick length
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    /// Get simulated time elapsed since start
+    pub fn elapsed(&self) -> Duration {
+        self.now - self.start
+    }
+
+    /// Create a time interval of the given length anchored at the start instant
+    pub fn interval(&self, length: Duration) -> TimeInterval {
+        TimeInterval::new(self.start, self.start + length)
+    }
+
+    /// Advance the clock by one tick, pacing against the wall clock if configured
+    pub async fn advance(&mut self) {
+        self.now += self.tick;
+        if let Some(pacing) = self.pacing {
+            tokio::time::sleep(pacing).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_clock_advance() {
+        let start = default_start_time();
+        let mut clock = SimClock::new(start, Duration::seconds(300));
+
+        clock.advance().await;
+        clock.advance().await;
+
+        assert_eq!(clock.now(), start + Duration::seconds(600));
+        assert_eq!(clock.elapsed(), Duration::seconds(600));
+        assert_eq!(clock.interval(Duration::days(1)).duration_secs(), 86_400);
+    }
+
+    #[tokio::test]
+    async fn test_clock_pacing_waits_per_tick() {
+        let start = default_start_time();
+        let pacing = std::time::Duration::from_millis(20);
+        let mut clock = SimClock::new(start, Duration::seconds(300)).with_pacing(pacing);
+
+        let wall = std::time::Instant::now();
+        clock.advance().await;
+        clock.advance().await;
+
+        assert!(wall.elapsed() >= pacing * 2);
+        // Pacing never changes simulated time
+        assert_eq!(clock.now(), start + Duration::seconds(600));
+    }
+}
@@ -1,7 +1,19 @@
 //! Simulation configuration
 
 use crate::error::{Result, SimulatorError};
+use crate::feedback::FeedbackConfig;
+use crate::fees::FeeConfig;
+use crate::network::OracleNetworkConfig;
+use crate::oracle::OracleConfig;
+use crate::orderbook::VenueConfig;
+use crate::participant::ParticipantBehavior;
+use crate::pricing::PricingConfig;
+use crate::resolution::ResolutionRuleConfig;
+use crate::settlement::SettlementConfig;
+use crate::timeseries::TimeSeriesConfig;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Configuration for market simulation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +34,44 @@ pub struct SimulationConfig {
     pub update_frequency_secs: u32,
     /// Random seed for reproducibility
     pub seed: Option<u64>,
+    /// Simulated start instant (defaults to 2024-01-01T00:00:00Z)
+    pub start_time: Option<DateTime<Utc>>,
+    /// Wall-clock milliseconds to wait per simulated tick (None = run as fast as possible)
+    pub pacing_ms: Option<u64>,
+    /// Rule deciding when the market resolves
+    #[serde(default)]
+    pub resolution_rule: ResolutionRuleConfig,
+    /// Per-tick time series capture (None = not recorded)
+    #[serde(default)]
+    pub time_series: Option<TimeSeriesConfig>,
+    /// Payout rules applied when the market resolves or expires
+    #[serde(default)]
+    pub settlement: SettlementConfig,
+    /// Mechanism pricing trades against the market
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    /// How participant orders are matched
+    #[serde(default)]
+    pub venue: VenueConfig,
+    /// Trading and settlement fees
+    #[serde(default)]
+    pub fees: FeeConfig,
+    /// Feedback from trading into the BSI (None = exogenous BSI)
+    #[serde(default)]
+    pub feedback: Option<FeedbackConfig>,
+    /// Oracle belief and observation model (None = derived from `volatility`)
+    ///
+    /// The update frequency is always taken from `update_frequency_secs`; a
+    /// calibrated oracle needs it set to the fitted frequency.
+    #[serde(default)]
+    pub oracle: Option<OracleConfig>,
+    /// Reporter network publishing the BSI (None = single reporter using the
+    /// `observation_*` settings of the oracle model)
+    #[serde(default)]
+    pub oracle_network: Option<OracleNetworkConfig>,
+    /// Relative weight of each behavior in the crowd (None = equal shares)
+    #[serde(default)]
+    pub participant_mix: Option<BTreeMap<ParticipantBehavior, f64>>,
 }
 
 impl SimulationConfig {
@@ -68,12 +118,44 @@ impl SimulationConfig {
             ));
         }
 
+        self.resolution_rule.validate()?;
+
+        if let Some(time_series) = &self.time_series {
+            time_series.validate()?;
+        }
+
+        self.settlement.validate()?;
+        self.pricing.validate()?;
+        self.venue.validate()?;
+        self.fees.validate()?;
+
+        if let Some(feedback) = &self.feedback {
+            feedback.validate()?;
+        }
+
+        if let Some(oracle) = &self.oracle {
+            oracle.validate()?;
+        }
+
+        if let Some(network) = &self.oracle_network {
+            network.validate()?;
+        }
+
+        if let Some(mix) = &self.participant_mix {
+            let valid_weights = mix.values().all(|w| w.is_finite() && *w >= 0.0);
+            if !valid_weights || mix.values().sum::<f64>() <= 0.0 {
+                return Err(SimulatorError::InvalidConfig(
+                    "Participant mix weights must be >= 0 with a positive total".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
 
 /// Builder for SimulationConfig
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct SimulationConfigBuilder {
     duration_days: Option<u32>,
     num_participants: Option<usize>,
@@ -83,6 +165,18 @@ pub struct SimulationConfigBuilder {
     persistence_hours: Option<u32>,
     update_frequency_secs: Option<u32>,
     seed: Option<u64>,
+    start_time: Option<DateTime<Utc>>,
+    pacing_ms: Option<u64>,
+    resolution_rule: Option<ResolutionRuleConfig>,
+    time_series: Option<TimeSeriesConfig>,
+    settlement: Option<SettlementConfig>,
+    pricing: Option<PricingConfig>,
+    venue: Option<VenueConfig>,
+    fees: Option<FeeConfig>,
+    feedback: Option<FeedbackConfig>,
+    oracle: Option<OracleConfig>,
+    oracle_network: Option<OracleNetworkConfig>,
+    participant_mix: Option<BTreeMap<ParticipantBehavior, f64>>,
 }
 
 impl SimulationConfigBuilder {
@@ -134,6 +228,78 @@ impl SimulationConfigBuilder {
         self
     }
 
+    /// Set simulated start instant
+    pub fn start_time(mut self, start: DateTime<Utc>) -> Self {
+        self.start_time = Some(start);
+        self
+    }
+
+    /// Set wall-clock pacing per simulated tick in milliseconds
+    pub fn pacing_ms(mut self, ms: u64) -> Self {
+        self.pacing_ms = Some(ms);
+        self
+    }
+
+    /// Set the market resolution rule
+    pub fn resolution_rule(mut self, rule: ResolutionRuleConfig) -> Self {
+        self.resolution_rule = Some(rule);
+        self
+    }
+
+    /// Record a per-tick time series
+    pub fn time_series(mut self, config: TimeSeriesConfig) -> Self {
+        self.time_series = Some(config);
+        self
+    }
+
+    /// Set settlement payout rules
+    pub fn settlement(mut self, settlement: SettlementConfig) -> Self {
+        self.settlement = Some(settlement);
+        self
+    }
+
+    /// Set the pricing mechanism
+    pub fn pricing(mut self, pricing: PricingConfig) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Set the order matching venue
+    pub fn venue(mut self, venue: VenueConfig) -> Self {
+        self.venue = Some(venue);
+        self
+    }
+
+    /// Set the fee schedule
+    pub fn fees(mut self, fees: FeeConfig) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+
+    /// Feed trading back into the BSI
+    pub fn feedback(mut self, config: FeedbackConfig) -> Self {
+        self.feedback = Some(config);
+        self
+    }
+
+    /// Override the oracle belief and observation model
+    pub fn oracle(mut self, config: OracleConfig) -> Self {
+        self.oracle = Some(config);
+        self
+    }
+
+    /// Publish the BSI through a network of reporter nodes
+    pub fn oracle_network(mut self, config: OracleNetworkConfig) -> Self {
+        self.oracle_network = Some(config);
+        self
+    }
+
+    /// Set the relative weight of each behavior in the crowd
+    pub fn participant_mix(mut self, mix: BTreeMap<ParticipantBehavior, f64>) -> Self {
+        self.participant_mix = Some(mix);
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> Result<SimulationConfig> {
         let config = SimulationConfig {
@@ -145,6 +311,18 @@ impl SimulationConfigBuilder {
             persistence_hours: self.persistence_hours.unwrap_or(24),
             update_frequency_secs: self.update_frequency_secs.unwrap_or(300),
             seed: self.seed,
+            start_time: self.start_time,
+            pacing_ms: self.pacing_ms,
+            resolution_rule: self.resolution_rule.unwrap_or_default(),
+            time_series: self.time_series,
+            settlement: self.settlement.unwrap_or_default(),
+            pricing: self.pricing.unwrap_or_default(),
+            venue: self.venue.unwrap_or_default(),
+            fees: self.fees.unwrap_or_default(),
+            feedback: self.feedback,
+            oracle: self.oracle,
+            oracle_network: self.oracle_network,
+            participant_mix: self.participant_mix,
         };
 
         config.validate()?;
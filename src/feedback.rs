@@ -0,0 +1,190 @@
+//! Feedback from trading into the belief signal
+//!
+//! Belief markets are reflexive: what participants trade can move the belief
+//! the oracle reports. The feedback model turns each tick's trading into an
+//! adjustment applied to a later oracle update.
+
+use crate::error::{Result, SimulatorError};
+use crate::types::{LiquidityRole, PositionType, Trade, TradeType, BSI};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Trading signal fed back into the BSI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeedbackSource {
+    /// Net YES order flow as a fraction of traded contracts, in `[-1, 1]`
+    #[default]
+    OrderFlow,
+    /// Gap between the market price and the BSI
+    MarketPrice,
+    /// Sum of order flow and market price signals
+    Combined,
+}
+
+/// Feedback model configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeedbackConfig {
+    /// BSI change per unit of signal
+    pub weight: f64,
+    /// Additional oracle updates before a tick's signal takes effect
+    pub lag_ticks: u32,
+    /// Signal fed back
+    pub source: FeedbackSource,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        FeedbackConfig {
+            weight: 0.02,
+            lag_ticks: 0,
+            source: FeedbackSource::default(),
+        }
+    }
+}
+
+impl FeedbackConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.weight) {
+            return Err(SimulatorError::InvalidConfig(
+                "Feedback weight must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Signal produced by one tick of trading
+    pub fn signal(&self, bsi: BSI, market_price: f64, trades: &[Trade]) -> f64 {
+        match self.source {
+            FeedbackSource::OrderFlow => order_flow_imbalance(trades),
+            FeedbackSource::MarketPrice => market_price - bsi.value(),
+            FeedbackSource::Combined => order_flow_imbalance(trades) + market_price - bsi.value(),
+        }
+    }
+}
+
+/// Net YES contracts demanded by takers over contracts traded, in `[-1, 1]`
+///
+/// Buying YES or selling NO counts as YES demand. Maker fills are skipped so
+/// that each order book match is counted once, from the aggressor's side.
+pub fn order_flow_imbalance(trades: &[Trade]) -> f64 {
+    let mut net = 0.0;
+    let mut gross = 0.0;
+    for trade in trades {
+        if trade.liquidity_role == Some(LiquidityRole::Maker) {
+            continue;
+        }
+        let buys = matches!(trade.trade_type, TradeType::Open | TradeType::Increase);
+        let yes = (trade.position_type == PositionType::Long) == buys;
+        net += if yes { trade.size } else { -trade.size };
+        gross += trade.size;
+    }
+
+    if gross > 0.0 {
+        net / gross
+    } else {
+        0.0
+    }
+}
+
+/// Summary of the feedback applied during a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeedbackStatistics {
+    /// Oracle updates that received a lagged adjustment
+    pub adjustments: usize,
+    /// Sum of adjustments
+    pub net_adjustment: f64,
+    /// Sum of absolute adjustments
+    pub gross_adjustment: f64,
+    /// Largest absolute adjustment
+    pub max_adjustment: f64,
+}
+
+/// Turns each tick's trading into a lagged BSI adjustment
+#[derive(Debug, Clone)]
+pub struct FeedbackModel {
+    config: FeedbackConfig,
+    pending: VecDeque<f64>,
+    statistics: FeedbackStatistics,
+}
+
+impl FeedbackModel {
+    /// Create a feedback model
+    pub fn new(config: FeedbackConfig) -> Self {
+        FeedbackModel {
+            config,
+            pending: VecDeque::with_capacity(config.lag_ticks as usize + 1),
+            statistics: FeedbackStatistics::default(),
+        }
+    }
+
+    /// Record a tick of trading and return the adjustment due at the next oracle update
+    pub fn observe(&mut self, bsi: BSI, market_price: f64, trades: &[Trade]) -> f64 {
+        self.pending
+            .push_back(self.config.weight * self.config.signal(bsi, market_price, trades));
+        if self.pending.len() <= self.config.lag_ticks as usize {
+            return 0.0;
+        }
+
+        let adjustment = self.pending.pop_front().unwrap_or(0.0);
+        self.statistics.adjustments += 1;
+        self.statistics.net_adjustment += adjustment;
+        self.statistics.gross_adjustment += adjustment.abs();
+        self.statistics.max_adjustment = self.statistics.max_adjustment.max(adjustment.abs());
+        adjustment
+    }
+
+    /// Get feedback statistics
+    pub fn statistics(&self) -> FeedbackStatistics {
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+
+    fn trade(trade_type: TradeType, position_type: PositionType, size: f64) -> Trade {
+        Trade {
+            id: "t".to_string(),
+            participant_id: "p".to_string(),
+            trade_type,
+            position_type,
+            size,
+            price: 0.5,
+            timestamp: default_start_time(),
+            bsi_at_trade: BSI::new(0.5).unwrap(),
+            realized_pnl: 0.0,
+            slippage: 0.0,
+            fee: 0.0,
+            order_id: None,
+            liquidity_role: None,
+            partial_fill: false,
+        }
+    }
+
+    #[test]
+    fn test_order_flow_signal_is_lagged() {
+        let config = FeedbackConfig {
+            weight: 0.1,
+            lag_ticks: 2,
+            source: FeedbackSource::OrderFlow,
+        };
+        let mut model = FeedbackModel::new(config);
+        let bsi = BSI::new(0.5).unwrap();
+        // Buying YES and selling NO are both YES demand; buying NO is not
+        let trades = [
+            trade(TradeType::Open, PositionType::Long, 30.0),
+            trade(TradeType::Close, PositionType::Short, 10.0),
+            trade(TradeType::Open, PositionType::Short, 20.0),
+        ];
+
+        assert_eq!(model.observe(bsi, 0.5, &trades), 0.0);
+        assert_eq!(model.observe(bsi, 0.5, &[]), 0.0);
+        assert!((model.observe(bsi, 0.5, &[]) - 0.1 * 20.0 / 60.0).abs() < 1e-12);
+        assert_eq!(model.observe(bsi, 0.5, &[]), 0.0);
+        assert_eq!(model.statistics().adjustments, 2);
+    }
+}
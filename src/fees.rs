@@ -0,0 +1,147 @@
+//! Trading and settlement fees
+
+use crate::error::{Result, SimulatorError};
+use crate::types::LiquidityRole;
+use serde::{Deserialize, Serialize};
+
+/// Basis points in a whole
+const BPS: f64 = 10_000.0;
+
+/// Fee schedule
+///
+/// Trading fees are charged on the notional of each trade; the settlement fee
+/// is withheld from payouts. Collected fees are split between the protocol
+/// treasury and liquidity providers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeeConfig {
+    /// Fee on orders that remove liquidity, including all instant fills
+    pub taker_fee_bps: u32,
+    /// Fee on resting orders that are filled
+    pub maker_fee_bps: u32,
+    /// Fee withheld from settlement payouts
+    pub settlement_fee_bps: u32,
+    /// Fraction of collected fees sent to the treasury; the rest goes to LPs
+    pub protocol_share: f64,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        FeeConfig {
+            taker_fee_bps: 0,
+            maker_fee_bps: 0,
+            settlement_fee_bps: 0,
+            protocol_share: 0.5,
+        }
+    }
+}
+
+impl FeeConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        let rates = [
+            self.taker_fee_bps,
+            self.maker_fee_bps,
+            self.settlement_fee_bps,
+        ];
+        if rates.iter().any(|&bps| bps as f64 > BPS) {
+            return Err(SimulatorError::InvalidConfig(
+                "Fees must not exceed 10000 bps".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.protocol_share) {
+            return Err(SimulatorError::InvalidConfig(
+                "Protocol fee share must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Fee on a trade of the given notional (`None` role = instant fill)
+    pub fn trade_fee(&self, notional: f64, role: Option<LiquidityRole>) -> f64 {
+        let bps = match role {
+            Some(LiquidityRole::Maker) => self.maker_fee_bps,
+            Some(LiquidityRole::Taker) | None => self.taker_fee_bps,
+        };
+        notional.abs() * bps as f64 / BPS
+    }
+
+    /// Fee withheld from a settlement payout
+    pub fn settlement_fee(&self, payout: f64) -> f64 {
+        payout.max(0.0) * self.settlement_fee_bps as f64 / BPS
+    }
+
+    /// Fraction of notional paid to enter and exit as a taker
+    pub fn round_trip_rate(&self) -> f64 {
+        2.0 * self.taker_fee_bps as f64 / BPS
+    }
+}
+
+/// Fees collected by a market
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeeLedger {
+    /// Fees charged on trades
+    pub trading_fees: f64,
+    /// Fees withheld from settlement payouts
+    pub settlement_fees: f64,
+    /// Share of fees sent to the protocol treasury
+    pub treasury: f64,
+    /// Share of fees sent to liquidity providers
+    pub liquidity_providers: f64,
+}
+
+impl FeeLedger {
+    /// Total fees collected
+    pub fn total(&self) -> f64 {
+        self.trading_fees + self.settlement_fees
+    }
+
+    /// Record a trading fee
+    pub fn record_trading_fee(&mut self, fee: f64, config: &FeeConfig) {
+        self.trading_fees += fee;
+        self.split(fee, config);
+    }
+
+    /// Record a settlement fee
+    pub fn record_settlement_fee(&mut self, fee: f64, config: &FeeConfig) {
+        self.settlement_fees += fee;
+        self.split(fee, config);
+    }
+
+    fn split(&mut self, fee: f64, config: &FeeConfig) {
+        let protocol = fee * config.protocol_share;
+        self.treasury += protocol;
+        self.liquidity_providers += fee - protocol;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_tiers_and_split() {
+        let config = FeeConfig {
+            taker_fee_bps: 30,
+            maker_fee_bps: 10,
+            settlement_fee_bps: 100,
+            protocol_share: 0.25,
+        };
+        assert!(config.validate().is_ok());
+
+        let mut ledger = FeeLedger::default();
+        let taker = config.trade_fee(1000.0, Some(LiquidityRole::Taker));
+        let maker = config.trade_fee(1000.0, Some(LiquidityRole::Maker));
+        assert!((taker - 3.0).abs() < 1e-9);
+        assert!((maker - 1.0).abs() < 1e-9);
+        assert_eq!(config.trade_fee(1000.0, None), taker);
+
+        ledger.record_trading_fee(taker + maker, &config);
+        ledger.record_settlement_fee(config.settlement_fee(400.0), &config);
+
+        assert!((ledger.total() - 8.0).abs() < 1e-9);
+        assert!((ledger.treasury - 2.0).abs() < 1e-9);
+        assert!((ledger.liquidity_providers - 6.0).abs() < 1e-9);
+    }
+}
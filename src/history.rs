@@ -0,0 +1,377 @@
+//! Historical BSI series: import, validation, resampling and replay
+//!
+//! Series are loaded from CSV or JSON Lines files, checked for time order and
+//! range, and can be replayed in place of the simulated latent belief.
+
+use crate::error::{Result, SimulatorError};
+use crate::types::BSI;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// One recorded BSI observation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BsiRecord {
+    /// Observation time
+    pub timestamp: DateTime<Utc>,
+    /// BSI value
+    pub value: f64,
+    /// Data source, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Confidence in the observation in `[0, 1]`, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+}
+
+/// Interval between consecutive records longer than the expected spacing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Gap {
+    /// Time of the record before the gap
+    pub start: DateTime<Utc>,
+    /// Time of the record after the gap
+    pub end: DateTime<Utc>,
+}
+
+impl Gap {
+    /// Length of the gap
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// A validated, non-empty BSI series in strictly increasing time order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BsiSeries {
+    records: Vec<BsiRecord>,
+}
+
+impl BsiSeries {
+    /// Create a series from records
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no records, timestamps do not strictly
+    /// increase, or a value or confidence lies outside `[0, 1]`
+    pub fn new(records: Vec<BsiRecord>) -> Result<Self> {
+        if records.is_empty() {
+            return Err(SimulatorError::DataError("BSI series is empty".to_string()));
+        }
+
+        for (i, record) in records.iter().enumerate() {
+            if !(0.0..=1.0).contains(&record.value) {
+                return Err(SimulatorError::DataError(format!(
+                    "record {}: BSI {} is outside [0, 1]",
+                    i + 1,
+                    record.value
+                )));
+            }
+            if record.confidence.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+                return Err(SimulatorError::DataError(format!(
+                    "record {}: confidence is outside [0, 1]",
+                    i + 1
+                )));
+            }
+            if i > 0 && record.timestamp <= records[i - 1].timestamp {
+                return Err(SimulatorError::DataError(format!(
+                    "record {}: timestamp {} does not follow {}",
+                    i + 1,
+                    record.timestamp,
+                    records[i - 1].timestamp
+                )));
+            }
+        }
+
+        Ok(BsiSeries { records })
+    }
+
+    /// Parse CSV with a header row
+    ///
+    /// The `timestamp` and `value` columns are required; `source` and
+    /// `confidence` are optional and may be left empty. Timestamps are RFC 3339
+    /// or Unix seconds. Fields are not quoted. Blank lines and lines starting
+    /// with `#` are skipped.
+    pub fn from_csv_str(input: &str) -> Result<Self> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let Some((_, header)) = lines.next() else {
+            return Err(SimulatorError::DataError("CSV has no header".to_string()));
+        };
+        let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+        let column = |name: &str| columns.iter().position(|c| c.eq_ignore_ascii_case(name));
+        let (Some(timestamp_col), Some(value_col)) = (column("timestamp"), column("value")) else {
+            return Err(SimulatorError::DataError(
+                "CSV header needs timestamp and value columns".to_string(),
+            ));
+        };
+        let source_col = column("source");
+        let confidence_col = column("confidence");
+
+        let mut records = Vec::new();
+        for (line_number, line) in lines {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != columns.len() {
+                return Err(SimulatorError::DataError(format!(
+                    "line {}: expected {} fields, found {}",
+                    line_number,
+                    columns.len(),
+                    fields.len()
+                )));
+            }
+            let field = |col: Option<usize>| col.map(|c| fields[c]).filter(|f| !f.is_empty());
+            let number = |text: &str| {
+                text.parse::<f64>().map_err(|e| {
+                    SimulatorError::DataError(format!("line {}: {}: {}", line_number, text, e))
+                })
+            };
+
+            records.push(BsiRecord {
+                timestamp: parse_timestamp(fields[timestamp_col]).ok_or_else(|| {
+                    SimulatorError::DataError(format!(
+                        "line {}: invalid timestamp {}",
+                        line_number, fields[timestamp_col]
+                    ))
+                })?,
+                value: number(fields[value_col])?,
+                source: field(source_col).map(str::to_string),
+                confidence: field(confidence_col).map(number).transpose()?,
+            });
+        }
+
+        Self::new(records)
+    }
+
+    /// Parse JSON Lines, one [`BsiRecord`] object per line with an RFC 3339 timestamp
+    pub fn from_jsonl_str(input: &str) -> Result<Self> {
+        let records = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| SimulatorError::DataError(format!("line {}: {}", i + 1, e)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::new(records)
+    }
+
+    /// Load a `.csv` file, or a JSON Lines file with any other extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)?;
+        let is_csv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        if is_csv {
+            Self::from_csv_str(&input)
+        } else {
+            Self::from_jsonl_str(&input)
+        }
+    }
+
+    /// Records in time order
+    pub fn records(&self) -> &[BsiRecord] {
+        &self.records
+    }
+
+    /// Number of records
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Always false; series hold at least one record
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Time of the first record
+    pub fn start(&self) -> DateTime<Utc> {
+        self.records[0].timestamp
+    }
+
+    /// Time of the last record
+    pub fn end(&self) -> DateTime<Utc> {
+        self.records[self.records.len() - 1].timestamp
+    }
+
+    /// Intervals between consecutive records longer than `max_gap`
+    pub fn gaps(&self, max_gap: Duration) -> Vec<Gap> {
+        self.records
+            .windows(2)
+            .filter(|pair| pair[1].timestamp - pair[0].timestamp > max_gap)
+            .map(|pair| Gap {
+                start: pair[0].timestamp,
+                end: pair[1].timestamp,
+            })
+            .collect()
+    }
+
+    /// Resample onto a regular grid of `step` from the first record
+    ///
+    /// Each grid point carries the latest record at or before it, so gaps
+    /// hold the last observed value. The grid runs to the first point at or
+    /// after the last record, so the final observation is never dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `step` is not positive
+    pub fn resample(&self, step: Duration) -> Result<Self> {
+        if step <= Duration::zero() {
+            return Err(SimulatorError::DataError(
+                "Resampling step must be positive".to_string(),
+            ));
+        }
+
+        let mut records = Vec::new();
+        let mut next = 0;
+        let mut time = self.start();
+        loop {
+            while next + 1 < self.records.len() && self.records[next + 1].timestamp <= time {
+                next += 1;
+            }
+            records.push(BsiRecord {
+                timestamp: time,
+                ..self.records[next].clone()
+            });
+            if time >= self.end() {
+                break;
+            }
+            time += step;
+        }
+
+        Ok(BsiSeries { records })
+    }
+
+    /// BSI path in time order, as taken by [`crate::backtest::Backtester::run`]
+    pub fn bsi_path(&self) -> Vec<(DateTime<Utc>, BSI)> {
+        self.records
+            .iter()
+            .filter_map(|r| BSI::new(r.value).ok().map(|bsi| (r.timestamp, bsi)))
+            .collect()
+    }
+}
+
+/// Parse an RFC 3339 timestamp or Unix seconds
+fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    match text.parse::<i64>() {
+        Ok(secs) => DateTime::from_timestamp(secs, 0),
+        Err(_) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|time| time.with_timezone(&Utc)),
+    }
+}
+
+/// Replays a historical series as the latent belief, one update per tick
+///
+/// Once the series is exhausted its last value is held.
+#[derive(Debug, Clone)]
+pub struct ReplayOracle {
+    series: BsiSeries,
+    position: usize,
+    current_bsi: BSI,
+}
+
+impl ReplayOracle {
+    /// Replay `series` resampled to one record per `step`
+    pub fn new(series: &BsiSeries, step: Duration) -> Result<Self> {
+        let series = series.resample(step)?;
+        let current_bsi = BSI::new(series.records[0].value).map_err(SimulatorError::DataError)?;
+        Ok(ReplayOracle {
+            series,
+            position: 0,
+            current_bsi,
+        })
+    }
+
+    /// Time of the first replayed update
+    pub fn start(&self) -> DateTime<Utc> {
+        self.series.start()
+    }
+
+    /// Whether every record has been replayed
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.series.len()
+    }
+
+    /// Next BSI in the series
+    pub fn next_bsi(&mut self) -> Result<BSI> {
+        if let Some(record) = self.series.records.get(self.position) {
+            self.current_bsi = BSI::new(record.value).map_err(SimulatorError::DataError)?;
+            self.position += 1;
+        }
+        Ok(self.current_bsi)
+    }
+
+    /// Get current BSI
+    pub fn current_bsi(&self) -> BSI {
+        self.current_bsi
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+
+    #[test]
+    fn test_csv_and_jsonl_agree() {
+        let start = default_start_time().timestamp();
+        let csv = format!(
+            "timestamp,value,source,confidence\n{},0.4,poll,0.9\n# outage\n{},0.6,,\n",
+            start,
+            start + 600
+        );
+        let jsonl = concat!(
+            r#"{"timestamp":"2024-01-01T00:00:00Z","value":0.4,"source":"poll","confidence":0.9}"#,
+            "\n",
+            r#"{"timestamp":"2024-01-01T00:10:00Z","value":0.6}"#,
+        );
+
+        let from_csv = BsiSeries::from_csv_str(&csv).unwrap();
+        let from_jsonl = BsiSeries::from_jsonl_str(jsonl).unwrap();
+
+        assert_eq!(from_csv, from_jsonl);
+        assert_eq!(from_csv.records()[0].source.as_deref(), Some("poll"));
+        assert_eq!(from_csv.gaps(Duration::minutes(5)).len(), 1);
+    }
+
+    #[test]
+    fn test_validation_rejects_bad_series() {
+        assert!(BsiSeries::from_csv_str("timestamp,value\n0,1.2\n").is_err());
+        assert!(BsiSeries::from_csv_str("timestamp,value\n60,0.5\n0,0.5\n").is_err());
+        assert!(BsiSeries::from_csv_str("time,bsi\n0,0.5\n").is_err());
+        assert!(BsiSeries::from_csv_str("timestamp,value\n").is_err());
+    }
+
+    #[test]
+    fn test_resample_and_replay_hold_last_value() {
+        let csv = "timestamp,value\n0,0.2\n250,0.5\n900,0.8\n";
+        let series = BsiSeries::from_csv_str(csv).unwrap();
+
+        let resampled = series.resample(Duration::seconds(300)).unwrap();
+        let values: Vec<f64> = resampled.records().iter().map(|r| r.value).collect();
+        assert_eq!(values, vec![0.2, 0.5, 0.5, 0.8]);
+
+        let mut replay = ReplayOracle::new(&series, Duration::seconds(300)).unwrap();
+        let replayed: Vec<f64> = (0..6).map(|_| replay.next_bsi().unwrap().value()).collect();
+        assert_eq!(replayed, vec![0.2, 0.5, 0.5, 0.8, 0.8, 0.8]);
+        assert!(replay.is_finished());
+
+        // A final record off the grid lands on the next grid point
+        let series = BsiSeries::from_csv_str("timestamp,value\n0,0.2\n301,0.6\n").unwrap();
+        let resampled = series.resample(Duration::seconds(300)).unwrap();
+        let records = resampled.records();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].value, 0.6);
+        assert_eq!(records[2].timestamp, series.start() + Duration::seconds(600));
+
+        let mut replay = ReplayOracle::new(&series, Duration::seconds(300)).unwrap();
+        let replayed: Vec<f64> = (0..3).map(|_| replay.next_bsi().unwrap().value()).collect();
+        assert_eq!(replayed, vec![0.2, 0.2, 0.6]);
+    }
+}
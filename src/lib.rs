@@ -46,24 +46,78 @@
 //! # }
 //! ```
 
+pub mod agent;
+pub mod backtest;
+pub mod belief;
+pub mod calibration;
+pub mod clock;
 pub mod config;
 pub mod error;
+pub mod feedback;
+pub mod fees;
+pub mod history;
 pub mod market;
+pub mod montecarlo;
+pub mod network;
 pub mod oracle;
+pub mod orderbook;
 pub mod participant;
+pub mod pricing;
+pub mod resolution;
+pub mod rng;
 pub mod scenario;
+pub mod sensitivity;
+pub mod settlement;
+pub mod significance;
 pub mod simulator;
 pub mod strategy;
+pub mod sweep;
+pub mod timeseries;
 pub mod types;
 pub mod analytics;
 
+pub use agent::{Agent, AgentAction, AgentObservation, BehaviorAgent, StrategyAgent};
+pub use backtest::{BacktestConfig, Backtester};
+pub use belief::{BeliefProcess, BeliefProcessConfig};
+pub use calibration::{Calibration, CalibrationModel, ResidualDiagnostics};
+pub use clock::SimClock;
 pub use config::SimulationConfig;
 pub use error::{SimulatorError, Result};
+pub use feedback::{FeedbackConfig, FeedbackSource, FeedbackStatistics};
+pub use fees::{FeeConfig, FeeLedger};
+pub use history::{BsiRecord, BsiSeries, Gap, ReplayOracle};
 pub use market::{Market, MarketState};
-pub use oracle::{OracleSimulator, OracleConfig};
+pub use montecarlo::{
+    CancelHandle, MonteCarloConfig, MonteCarloProgress, MonteCarloRunner, MonteCarloSummary,
+};
+pub use network::{
+    Aggregator, NodeBehavior, OracleNetwork, OracleNetworkConfig, OracleNetworkStatistics,
+    OracleNode,
+};
+pub use oracle::{OracleSimulator, OracleConfig, OracleObserver};
+pub use orderbook::{OrderBook, OrderBookConfig, VenueConfig};
 pub use participant::{Participant, ParticipantBehavior};
-pub use scenario::Scenario;
-pub use simulator::{Simulator, SimulationResult};
-pub use strategy::{Strategy, StrategyBacktest};
-pub use types::{BSI, Position, Trade};
-pub use analytics::{Analytics, PerformanceMetrics};
+pub use pricing::{PricingConfig, PricingMechanism};
+pub use resolution::{ResolutionAccuracy, ResolutionRule, ResolutionRuleConfig};
+pub use rng::{RngStreams, SimRng};
+pub use scenario::{
+    Scenario, ScenarioAction, ScenarioDefinition, ScenarioEvent, ScenarioTimeline, Trigger,
+};
+pub use sensitivity::{
+    MorrisEffect, MorrisReport, Sensitivity, SensitivityOutput, SobolIndex, SobolReport,
+};
+pub use settlement::{RefundRule, SettlementConfig, SettlementReport};
+pub use significance::{
+    ComparisonMatrix, ComparisonMetric, Correction, PairwiseTest, SignificanceTest,
+};
+pub use simulator::{ParticipantPnl, SimulationResult, Simulator};
+pub use strategy::{
+    MarketObservation, PositionSizing, Strategy, StrategyAction, StrategyBacktest, TradingStrategy,
+};
+pub use sweep::{Axis, Sweep, SweepPoint, SweepRow, SweepTable};
+pub use timeseries::{TickSnapshot, TimeSeries, TimeSeriesConfig};
+pub use types::{BSI, LiquidityRole, Position, Trade};
+pub use analytics::{
+    Analytics, ConfidenceInterval, DistributionAccumulator, DistributionReport,
+    DistributionSummary, Histogram, MetricsAccumulator, OracleReliability, PerformanceMetrics,
+};
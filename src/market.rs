@@ -1,8 +1,15 @@
 //! Market state and lifecycle management
 
-use crate::types::{BSI, Position, Trade, TimeInterval};
-use chrono::{DateTime, Utc};
+use crate::clock::SimClock;
+use crate::error::{Result, SimulatorError};
+use crate::fees::{FeeConfig, FeeLedger};
+use crate::orderbook::{OrderBook, OrderBookStatistics, OrderResult, OrderSide};
+use crate::pricing::{Fill, OraclePricing, PricingMechanism};
+use crate::resolution::{PersistenceWindow, ResolutionRule, RuleStatus, ThresholdDirection};
+use crate::types::{BSI, Position, PositionType, Trade, TimeInterval};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Market state
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,14 +22,38 @@ pub struct Market {
     pub current_bsi: BSI,
     /// BSI threshold for resolution
     pub threshold: f64,
+    /// Resolution rule (None = resolve on first update at or above threshold)
+    #[serde(skip)]
+    pub resolution_rule: Option<Box<dyn ResolutionRule>>,
+    /// Pricing mechanism (None = fill at the oracle BSI)
+    #[serde(skip)]
+    pub pricing: Option<Box<dyn PricingMechanism>>,
+    /// Limit order book (None = instant fills against the pricing mechanism)
+    pub order_book: Option<OrderBook>,
+    /// Status reported by the resolution rule on the latest update
+    pub rule_status: RuleStatus,
+    /// Start of the current run of updates satisfying the resolution condition
+    pub threshold_held_since: Option<DateTime<Utc>>,
+    /// First time the resolution condition held
+    pub first_crossing_time: Option<DateTime<Utc>>,
     /// Time interval
     pub time_interval: TimeInterval,
     /// All trades
     pub trades: Vec<Trade>,
-    /// Active positions
-    pub positions: Vec<Position>,
+    /// Active net positions by participant ID
+    pub positions: BTreeMap<String, Position>,
     /// Total volume
     pub total_volume: f64,
+    /// Total slippage paid by traders
+    pub total_slippage: f64,
+    /// Profit and loss realized by all participants
+    pub realized_pnl: f64,
+    /// Net cash paid into the market by trades
+    pub collateral: f64,
+    /// Fee schedule charged on trades and settlement
+    pub fee_schedule: FeeConfig,
+    /// Fees collected
+    pub fees: FeeLedger,
     /// Resolution time (if resolved)
     pub resolution_time: Option<DateTime<Utc>>,
 }
@@ -40,46 +71,237 @@ impl Market {
             state: MarketState::Active,
             current_bsi: initial_bsi,
             threshold,
+            resolution_rule: None,
+            pricing: None,
+            order_book: None,
+            rule_status: RuleStatus::NotMet,
+            threshold_held_since: None,
+            first_crossing_time: None,
             time_interval,
             trades: Vec::new(),
-            positions: Vec::new(),
+            positions: BTreeMap::new(),
             total_volume: 0.0,
+            total_slippage: 0.0,
+            realized_pnl: 0.0,
+            collateral: 0.0,
+            fee_schedule: FeeConfig::default(),
+            fees: FeeLedger::default(),
             resolution_time: None,
         }
     }
 
-    /// Update market BSI
-    pub fn update_bsi(&mut self, new_bsi: BSI) {
+    /// Require the threshold to hold for `persistence` before resolving
+    pub fn with_persistence(self, persistence: Duration) -> Self {
+        let rule = PersistenceWindow::new(self.threshold, ThresholdDirection::Above, persistence);
+        self.with_resolution_rule(Box::new(rule))
+    }
+
+    /// Delegate resolution decisions to `rule`
+    pub fn with_resolution_rule(mut self, rule: Box<dyn ResolutionRule>) -> Self {
+        self.resolution_rule = Some(rule);
+        self
+    }
+
+    /// Price trades through `pricing` instead of at the oracle BSI
+    pub fn with_pricing(mut self, pricing: Box<dyn PricingMechanism>) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Charge fees according to `schedule`
+    pub fn with_fees(mut self, schedule: FeeConfig) -> Self {
+        self.fee_schedule = schedule;
+        self
+    }
+
+    /// Match orders on a limit order book instead of filling instantly
+    pub fn with_order_book(mut self, order_book: OrderBook) -> Self {
+        self.order_book = Some(order_book);
+        self
+    }
+
+    /// Current YES price
+    ///
+    /// With an order book this is the quote midpoint, falling back to the
+    /// last trade and then the BSI while the book is one-sided.
+    pub fn market_price(&self) -> f64 {
+        match (&self.order_book, &self.pricing) {
+            (Some(book), _) => book.reference_price().unwrap_or(self.current_bsi.value()),
+            (None, Some(pricing)) => pricing.price(),
+            (None, None) => self.current_bsi.value(),
+        }
+    }
+
+    /// Execute `size` contracts of `side` (negative to sell) against the pricing mechanism
+    pub fn execute(&mut self, side: PositionType, size: f64) -> Result<Fill> {
+        let bsi = self.current_bsi;
+        let fill = self
+            .pricing
+            .get_or_insert_with(|| Box::new(OraclePricing::new(bsi)))
+            .execute(side, size)?;
+        self.total_slippage += fill.slippage;
+        Ok(fill)
+    }
+
+    /// Collateral cost of buying `size` contracts of `side` against the pricing mechanism
+    pub fn quote(&self, side: PositionType, size: f64) -> Result<f64> {
+        match &self.pricing {
+            Some(pricing) => pricing.quote(side, size),
+            None => Ok(size * side.unit_price(self.current_bsi.value())),
+        }
+    }
+
+    /// Submit a limit (`Some(price)`) or market (`None`) order to the order book
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the market has no order book or is not active
+    pub fn submit_order(
+        &mut self,
+        participant_id: &str,
+        side: OrderSide,
+        limit_price: Option<f64>,
+        size: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<OrderResult> {
+        if self.state != MarketState::Active {
+            return Err(SimulatorError::InvalidMarketState(format!(
+                "cannot submit orders to a market in state {:?}",
+                self.state
+            )));
+        }
+        let book = self.order_book.as_mut().ok_or_else(|| {
+            SimulatorError::InvalidMarketState("market has no order book".to_string())
+        })?;
+
+        let result = book.submit(participant_id, side, limit_price, size, timestamp);
+        // Taker slippage is measured against the best quote at submission
+        if let Some(first) = result.executions.first() {
+            self.total_slippage += result
+                .executions
+                .iter()
+                .map(|e| e.size * (e.price - first.price).abs())
+                .sum::<f64>();
+        }
+        Ok(result)
+    }
+
+    /// Cancel a participant's resting orders
+    pub fn cancel_orders(&mut self, participant_id: &str) -> usize {
+        self.order_book
+            .as_mut()
+            .map_or(0, |book| book.cancel_participant(participant_id))
+    }
+
+    /// Name of the resolution rule in use
+    pub fn resolution_rule_name(&self) -> &'static str {
+        self.resolution_rule
+            .as_ref()
+            .map_or("first_crossing", |rule| rule.name())
+    }
+
+    /// Update market BSI observed at `timestamp`
+    pub fn update_bsi(&mut self, new_bsi: BSI, timestamp: DateTime<Utc>) {
         self.current_bsi = new_bsi;
+        if let Some(pricing) = self.pricing.as_mut() {
+            pricing.on_oracle_update(new_bsi);
+        }
+
+        self.rule_status = match self.resolution_rule.as_mut() {
+            Some(rule) => rule.observe(new_bsi, timestamp),
+            None if new_bsi.value() >= self.threshold => RuleStatus::Met,
+            None => RuleStatus::NotMet,
+        };
+
+        if self.rule_status == RuleStatus::NotMet {
+            // Condition no longer holds, so the current run is over
+            self.threshold_held_since = None;
+        } else {
+            self.threshold_held_since.get_or_insert(timestamp);
+            self.first_crossing_time.get_or_insert(timestamp);
+        }
     }
 
     /// Add a trade to the market
     pub fn add_trade(&mut self, trade: Trade) {
         self.total_volume += trade.size;
+        self.realized_pnl += trade.realized_pnl;
+        self.collateral -= trade.cash_flow();
+        self.fees.record_trading_fee(trade.fee, &self.fee_schedule);
         self.trades.push(trade);
     }
 
-    /// Add a position
-    pub fn add_position(&mut self, position: Position) {
-        self.positions.push(position);
+    /// Replace a participant's net position, removing it when `None`
+    pub fn set_position(&mut self, participant_id: &str, position: Option<Position>) {
+        match position {
+            Some(position) => {
+                self.positions.insert(participant_id.to_string(), position);
+            }
+            None => {
+                self.positions.remove(participant_id);
+            }
+        }
+    }
+
+    /// Unrealized profit and loss of open positions at the given YES price
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        self.positions.values().map(|p| p.unrealized_pnl(price)).sum()
+    }
+
+    /// Total size of open positions
+    pub fn open_interest(&self) -> f64 {
+        self.positions.values().map(|p| p.size).sum()
     }
 
     /// Check if market should resolve
-    pub fn should_resolve(&self, current_time: DateTime<Utc>) -> bool {
-        // Check if BSI crossed threshold
-        let threshold_crossed = self.current_bsi.value() >= self.threshold;
-        
+    pub fn should_resolve(&self, clock: &SimClock) -> bool {
+        // Check if the resolution rule is satisfied
+        let rule_met = self.rule_status == RuleStatus::Met;
+
         // Check if within time interval
-        let within_interval = current_time >= self.time_interval.start
-            && current_time <= self.time_interval.end;
+        let within_interval = self.time_interval.contains(clock.now());
 
-        threshold_crossed && within_interval
+        rule_met && within_interval
     }
 
     /// Resolve the market
     pub fn resolve(&mut self, resolution_time: DateTime<Utc>) {
         self.state = MarketState::Resolved;
         self.resolution_time = Some(resolution_time);
+        self.close_order_book();
+    }
+
+    /// Halt trading and cancel resting orders
+    pub fn pause(&mut self) {
+        if self.state == MarketState::Active {
+            self.state = MarketState::Paused;
+            self.close_order_book();
+        }
+    }
+
+    /// Resume trading after a pause
+    pub fn resume(&mut self) {
+        if self.state == MarketState::Paused {
+            self.state = MarketState::Active;
+        }
+    }
+
+    /// Whether the market is still open, trading or paused
+    pub fn is_open(&self) -> bool {
+        matches!(self.state, MarketState::Active | MarketState::Paused)
+    }
+
+    /// Expire the market after its interval ends without resolution
+    pub fn expire(&mut self) {
+        self.state = MarketState::Expired;
+        self.close_order_book();
+    }
+
+    /// Cancel resting orders once trading stops
+    fn close_order_book(&mut self) {
+        if let Some(book) = self.order_book.as_mut() {
+            book.clear();
+        }
     }
 
     /// Get market statistics
@@ -88,11 +310,21 @@ impl Market {
             total_trades: self.trades.len(),
             total_volume: self.total_volume,
             active_positions: self.positions.len(),
+            open_interest: self.open_interest(),
+            realized_pnl: self.realized_pnl,
+            unrealized_pnl: self.unrealized_pnl(self.market_price()),
+            market_price: self.market_price(),
+            total_slippage: self.total_slippage,
+            lp_worst_case_loss: self.pricing.as_ref().and_then(|p| p.worst_case_loss()),
+            order_book: self.order_book.as_ref().map(OrderBook::statistics),
+            fees: self.fees,
             current_bsi: self.current_bsi.value(),
             threshold: self.threshold,
             time_to_resolution: self.resolution_time.map(|rt| {
                 (rt - self.time_interval.start).num_seconds()
             }),
+            first_crossing_time: self.first_crossing_time,
+            resolution_time: self.resolution_time,
         }
     }
 }
@@ -111,7 +343,7 @@ pub enum MarketState {
 }
 
 /// Market statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MarketStatistics {
     /// Total number of trades
     pub total_trades: usize,
@@ -119,23 +351,44 @@ pub struct MarketStatistics {
     pub total_volume: f64,
     /// Number of active positions
     pub active_positions: usize,
+    /// Total size of open positions
+    pub open_interest: f64,
+    /// Profit and loss realized by all participants
+    pub realized_pnl: f64,
+    /// Unrealized profit and loss marked at the market price
+    pub unrealized_pnl: f64,
+    /// Current YES price
+    pub market_price: f64,
+    /// Total slippage paid by traders
+    pub total_slippage: f64,
+    /// Maximum loss the liquidity provider can incur (None = unbounded)
+    pub lp_worst_case_loss: Option<f64>,
+    /// Spread, depth and queue statistics (None = instant fills)
+    pub order_book: Option<OrderBookStatistics>,
+    /// Fees collected so far
+    pub fees: FeeLedger,
     /// Current BSI value
     pub current_bsi: f64,
     /// Resolution threshold
     pub threshold: f64,
     /// Time to resolution in seconds (if resolved)
     pub time_to_resolution: Option<i64>,
+    /// First time the BSI reached the threshold
+    pub first_crossing_time: Option<DateTime<Utc>>,
+    /// Confirmed resolution time after the persistence window
+    pub resolution_time: Option<DateTime<Utc>>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::default_start_time;
     use chrono::Duration;
 
     #[test]
     fn test_market_creation() {
         let initial_bsi = BSI::new(0.5).unwrap();
-        let start = Utc::now();
+        let start = default_start_time();
         let end = start + Duration::days(30);
         let interval = TimeInterval::new(start, end);
 
@@ -153,9 +406,8 @@ mod tests {
     #[test]
     fn test_market_resolution() {
         let initial_bsi = BSI::new(0.5).unwrap();
-        let start = Utc::now();
-        let end = start + Duration::days(30);
-        let interval = TimeInterval::new(start, end);
+        let clock = SimClock::new(default_start_time(), Duration::minutes(5));
+        let interval = clock.interval(Duration::days(30));
 
         let mut market = Market::new(
             "test-market".to_string(),
@@ -165,11 +417,71 @@ mod tests {
         );
 
         // Update BSI to cross threshold
-        market.update_bsi(BSI::new(0.8).unwrap());
+        market.update_bsi(BSI::new(0.8).unwrap(), clock.now());
 
-        assert!(market.should_resolve(Utc::now()));
+        assert!(market.should_resolve(&clock));
 
-        market.resolve(Utc::now());
+        market.resolve(clock.now());
         assert_eq!(market.state, MarketState::Resolved);
     }
+
+    #[test]
+    fn test_set_position_keys_by_participant() {
+        let start = default_start_time();
+        let interval = TimeInterval::new(start, start + Duration::days(1));
+        let mut market = Market::new("test-market".to_string(), BSI::default(), 0.75, interval);
+        let position = |size| Position {
+            participant_id: "p-0".to_string(),
+            size,
+            entry_price: 0.4,
+            entry_time: start,
+            position_type: PositionType::Long,
+        };
+
+        market.set_position("p-0", Some(position(10.0)));
+        market.set_position("p-0", Some(position(4.0)));
+        assert_eq!(market.positions.len(), 1);
+        assert_eq!(market.open_interest(), 4.0);
+
+        market.set_position("p-0", None);
+        assert!(market.positions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_persistence_window() {
+        let mut clock = SimClock::new(default_start_time(), Duration::hours(1));
+        let interval = clock.interval(Duration::days(30));
+        let mut market = Market::new(
+            "test-market".to_string(),
+            BSI::new(0.5).unwrap(),
+            0.75,
+            interval,
+        )
+        .with_persistence(Duration::hours(2));
+
+        let above = BSI::new(0.8).unwrap();
+        let below = BSI::new(0.7).unwrap();
+        let first_crossing = clock.now();
+
+        // Cross, then drop back before the window elapses
+        market.update_bsi(above, clock.now());
+        assert!(!market.should_resolve(&clock));
+        clock.advance().await;
+        market.update_bsi(below, clock.now());
+        assert!(market.threshold_held_since.is_none());
+
+        // Hold above threshold for the full window
+        clock.advance().await;
+        let held_since = clock.now();
+        market.update_bsi(above, clock.now());
+        clock.advance().await;
+        market.update_bsi(above, clock.now());
+        assert!(!market.should_resolve(&clock));
+        clock.advance().await;
+        market.update_bsi(above, clock.now());
+        assert!(market.should_resolve(&clock));
+
+        assert_eq!(market.threshold_held_since, Some(held_since));
+        assert_eq!(market.first_crossing_time, Some(first_crossing));
+    }
 }
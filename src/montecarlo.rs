@@ -0,0 +1,316 @@
+//! Parallel Monte Carlo batches of seeded runs
+//!
+//! Runs execute on Tokio's blocking thread pool, each from a seed derived
+//! from the batch's master seed, so a batch is reproducible regardless of
+//! how many runs execute at once. Results are delivered in run order and
+//! folded into the metrics as they arrive instead of being collected.
+
+use crate::analytics::{MetricsAccumulator, PerformanceMetrics};
+use crate::error::{Result, SimulatorError};
+use crate::rng::{RngStreams, RUN_STREAM};
+use crate::scenario::{Scenario, ScenarioDefinition};
+use crate::simulator::{SimulationResult, Simulator};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+/// Batch configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonteCarloConfig {
+    /// Number of runs
+    pub runs: usize,
+    /// Seed from which every run's seed is derived
+    pub master_seed: u64,
+    /// Runs executing at once; 0 uses every available core
+    pub max_parallel: usize,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        MonteCarloConfig {
+            runs: 100,
+            master_seed: 0,
+            max_parallel: 0,
+        }
+    }
+}
+
+impl MonteCarloConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if self.runs == 0 {
+            return Err(SimulatorError::InvalidConfig(
+                "Number of runs must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Seed of run `index`
+    pub fn run_seed(&self, index: usize) -> u64 {
+        RngStreams::new(self.master_seed).derive_seed(RUN_STREAM, index as u64)
+    }
+
+    fn parallelism(&self) -> usize {
+        match self.max_parallel {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+}
+
+/// Progress of a batch, reported after each delivered run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonteCarloProgress {
+    /// Runs delivered so far
+    pub completed: usize,
+    /// Runs requested
+    pub total: usize,
+}
+
+/// Outcome of a batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloSummary {
+    /// Runs requested
+    pub runs_requested: usize,
+    /// Runs completed and analyzed
+    pub runs_completed: usize,
+    /// Whether the batch was cancelled before every run completed
+    pub cancelled: bool,
+    /// Metrics of the completed runs
+    pub metrics: PerformanceMetrics,
+}
+
+/// Handle for cancelling a running batch
+///
+/// Runs already executing finish and are delivered; no new runs start.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    /// Stop starting new runs
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the batch was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(MonteCarloProgress) + Send + Sync>;
+
+/// Executes seeded runs of a simulator across all cores
+pub struct MonteCarloRunner {
+    simulator: Arc<Simulator>,
+    config: MonteCarloConfig,
+    progress: Option<ProgressCallback>,
+    cancel: CancelHandle,
+}
+
+impl MonteCarloRunner {
+    /// Create a runner
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid
+    pub fn new(simulator: Simulator, config: MonteCarloConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(MonteCarloRunner {
+            simulator: Arc::new(simulator),
+            config,
+            progress: None,
+            cancel: CancelHandle::default(),
+        })
+    }
+
+    /// Call `callback` after each run is delivered
+    pub fn with_progress(
+        mut self,
+        callback: impl Fn(MonteCarloProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Handle that cancels the batch from another task or thread
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Run the batch for a predefined scenario
+    ///
+    /// Each result is passed to `on_result` in run order and then dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failed run
+    pub async fn run(
+        &self,
+        scenario: Scenario,
+        on_result: impl FnMut(&SimulationResult),
+    ) -> Result<MonteCarloSummary> {
+        self.execute(scenario, scenario.definition(), on_result)
+            .await
+    }
+
+    /// Run the batch for a custom scenario timeline
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the definition is invalid or a run fails
+    pub async fn run_custom(
+        &self,
+        definition: &ScenarioDefinition,
+        on_result: impl FnMut(&SimulationResult),
+    ) -> Result<MonteCarloSummary> {
+        definition.validate()?;
+        self.execute(Scenario::Custom, definition.clone(), on_result)
+            .await
+    }
+
+    async fn execute(
+        &self,
+        scenario: Scenario,
+        definition: ScenarioDefinition,
+        mut on_result: impl FnMut(&SimulationResult),
+    ) -> Result<MonteCarloSummary> {
+        let total = self.config.runs;
+        let parallel = self.config.parallelism();
+        let definition = Arc::new(definition);
+        let handle = tokio::runtime::Handle::current();
+
+        let mut tasks = JoinSet::new();
+        // Results that finished ahead of an earlier run, keyed by run index
+        let mut pending = BTreeMap::new();
+        let mut accumulator = MetricsAccumulator::new();
+        let mut launched = 0;
+
+        loop {
+            // Keep `parallel` runs executing, buffering at most as many finished early
+            while launched < total
+                && tasks.len() < parallel
+                && launched - accumulator.runs() < 2 * parallel
+                && !self.cancel.is_cancelled()
+            {
+                let simulator = Arc::clone(&self.simulator);
+                let definition = Arc::clone(&definition);
+                let handle = handle.clone();
+                let index = launched;
+                let seed = self.config.run_seed(index);
+                tasks.spawn_blocking(move || {
+                    let run = simulator.run_timeline(scenario, &definition, Some(seed));
+                    (index, handle.block_on(run))
+                });
+                launched += 1;
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            let (index, result) =
+                joined.map_err(|e| SimulatorError::SimulationFailed(e.to_string()))?;
+            pending.insert(index, result?);
+
+            while let Some(result) = pending.remove(&accumulator.runs()) {
+                accumulator.add(&result);
+                on_result(&result);
+                if let Some(progress) = &self.progress {
+                    progress(MonteCarloProgress {
+                        completed: accumulator.runs(),
+                        total,
+                    });
+                }
+            }
+        }
+
+        Ok(MonteCarloSummary {
+            runs_requested: total,
+            runs_completed: accumulator.runs(),
+            cancelled: accumulator.runs() < total,
+            metrics: accumulator.metrics(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::Analytics;
+    use crate::config::SimulationConfig;
+    use std::sync::atomic::AtomicUsize;
+
+    fn simulator() -> Simulator {
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(10)
+            .build()
+            .unwrap();
+        Simulator::new(config)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_batch_matches_sequential_runs() {
+        let config = MonteCarloConfig {
+            runs: 8,
+            master_seed: 5,
+            max_parallel: 3,
+        };
+        let reported = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&reported);
+        let runner = MonteCarloRunner::new(simulator(), config)
+            .unwrap()
+            .with_progress(move |progress| {
+                counter.store(progress.completed, Ordering::SeqCst);
+            });
+
+        let mut seeds = Vec::new();
+        let summary = runner
+            .run(Scenario::HighVolatility, |result| seeds.push(result.seed))
+            .await
+            .unwrap();
+
+        let mut sequential = Vec::new();
+        for index in 0..8 {
+            let seed = config.run_seed(index);
+            sequential.push(
+                simulator()
+                    .run_with_seed(Scenario::HighVolatility, seed)
+                    .await
+                    .unwrap(),
+            );
+        }
+        let expected: Vec<u64> = sequential.iter().map(|r| r.seed).collect();
+
+        assert_eq!(seeds, expected);
+        assert_eq!(summary.metrics, Analytics::analyze(&sequential));
+        assert_eq!(reported.load(Ordering::SeqCst), 8);
+        assert!(!summary.cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_batch_stops_starting_runs() {
+        let config = MonteCarloConfig {
+            runs: 50,
+            master_seed: 5,
+            max_parallel: 1,
+        };
+        let runner = MonteCarloRunner::new(simulator(), config).unwrap();
+        let cancel = runner.cancel_handle();
+
+        let summary = runner
+            .run(Scenario::Sideways, |_| cancel.cancel())
+            .await
+            .unwrap();
+
+        assert!(summary.cancelled);
+        assert_eq!(summary.runs_completed, 1);
+        assert_eq!(summary.metrics.total_simulations, 1);
+    }
+}
@@ -0,0 +1,399 @@
+//! Multi-node oracle networks
+//!
+//! A network of reporter nodes observes the latent belief, each with its own
+//! noise, latency and uptime. Fresh reports are aggregated into the BSI the
+//! market sees; adversarial nodes can bias their reports or collude on a
+//! common value.
+
+use crate::error::{Result, SimulatorError};
+use crate::types::BSI;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// How a node turns the belief it observes into a report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum NodeBehavior {
+    /// Report the observed belief
+    #[default]
+    Honest,
+    /// Report the observed belief shifted by `offset`
+    Biased { offset: f64 },
+    /// Report `target` exactly, in coordination with other colluders
+    Colluding { target: f64 },
+}
+
+/// Reporter node configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OracleNode {
+    /// Stake backing the node's reports
+    pub stake: f64,
+    /// Standard deviation of noise added to each report
+    pub noise: f64,
+    /// Updates by which the node's view lags the latent belief
+    pub latency_ticks: u32,
+    /// Probability of reporting on each update
+    pub uptime: f64,
+    /// Reporting behavior
+    pub behavior: NodeBehavior,
+}
+
+impl Default for OracleNode {
+    fn default() -> Self {
+        OracleNode {
+            stake: 1.0,
+            noise: 0.02,
+            latency_ticks: 0,
+            uptime: 0.99,
+            behavior: NodeBehavior::Honest,
+        }
+    }
+}
+
+impl OracleNode {
+    /// Whether the node deviates from honest reporting
+    pub fn is_faulty(&self) -> bool {
+        self.behavior != NodeBehavior::Honest
+    }
+}
+
+/// Rule combining fresh node reports into one value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Aggregator {
+    /// Median of reports
+    #[default]
+    Median,
+    /// Mean after dropping `trim_fraction` of reports from each end
+    TrimmedMean { trim_fraction: f64 },
+    /// Median weighted by node stake
+    StakeWeighted,
+}
+
+impl Aggregator {
+    /// Aggregate `(value, stake)` reports; `None` if there are none
+    pub fn aggregate(&self, reports: &[(f64, f64)]) -> Option<f64> {
+        if reports.is_empty() {
+            return None;
+        }
+
+        let mut sorted = reports.to_vec();
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let n = sorted.len();
+
+        let value = match self {
+            Aggregator::Median if n % 2 == 1 => sorted[n / 2].0,
+            Aggregator::Median => (sorted[n / 2 - 1].0 + sorted[n / 2].0) / 2.0,
+            Aggregator::TrimmedMean { trim_fraction } => {
+                let trim = ((n as f64 * trim_fraction).floor() as usize).min((n - 1) / 2);
+                let kept = &sorted[trim..n - trim];
+                kept.iter().map(|(value, _)| value).sum::<f64>() / kept.len() as f64
+            }
+            Aggregator::StakeWeighted => {
+                let total: f64 = sorted.iter().map(|(_, stake)| stake).sum();
+                let mut cumulative = 0.0;
+                sorted
+                    .iter()
+                    .find(|(_, stake)| {
+                        cumulative += stake;
+                        cumulative >= total / 2.0
+                    })
+                    .map_or(sorted[n - 1].0, |(value, _)| *value)
+            }
+        };
+        Some(value)
+    }
+}
+
+/// Oracle network configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OracleNetworkConfig {
+    /// Reporter nodes
+    pub nodes: Vec<OracleNode>,
+    /// Rule combining fresh reports
+    pub aggregator: Aggregator,
+    /// Updates after which a node's last report is ignored
+    pub max_staleness_ticks: u32,
+    /// Fresh reports needed to publish; otherwise the last value is held
+    pub min_reports: usize,
+}
+
+impl Default for OracleNetworkConfig {
+    fn default() -> Self {
+        OracleNetworkConfig::honest(7)
+    }
+}
+
+impl OracleNetworkConfig {
+    /// Network of `count` honest nodes that publishes with a majority of reports
+    pub fn honest(count: usize) -> Self {
+        OracleNetworkConfig {
+            nodes: vec![OracleNode::default(); count],
+            aggregator: Aggregator::default(),
+            max_staleness_ticks: 3,
+            min_reports: count / 2 + 1,
+        }
+    }
+
+    /// Replace the first `count` nodes with adversaries behaving as `behavior`
+    pub fn with_adversaries(mut self, count: usize, behavior: NodeBehavior) -> Self {
+        for node in self.nodes.iter_mut().take(count) {
+            node.behavior = behavior;
+        }
+        self
+    }
+
+    /// Number of faulty nodes
+    pub fn faulty_nodes(&self) -> usize {
+        self.nodes.iter().filter(|node| node.is_faulty()).count()
+    }
+
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if self.nodes.is_empty() {
+            return Err(SimulatorError::InvalidConfig(
+                "Oracle network must have at least 1 node".to_string(),
+            ));
+        }
+
+        for node in &self.nodes {
+            if !(node.stake.is_finite() && node.stake > 0.0) {
+                return Err(SimulatorError::InvalidConfig(
+                    "Oracle node stake must be greater than 0".to_string(),
+                ));
+            }
+
+            if !(0.0..=1.0).contains(&node.noise) || !(0.0..=1.0).contains(&node.uptime) {
+                return Err(SimulatorError::InvalidConfig(
+                    "Oracle node noise and uptime must be between 0.0 and 1.0".to_string(),
+                ));
+            }
+        }
+
+        if let Aggregator::TrimmedMean { trim_fraction } = self.aggregator {
+            if !(0.0..0.5).contains(&trim_fraction) {
+                return Err(SimulatorError::InvalidConfig(
+                    "Trim fraction must be at least 0.0 and below 0.5".to_string(),
+                ));
+            }
+        }
+
+        if self.min_reports == 0 || self.min_reports > self.nodes.len() {
+            return Err(SimulatorError::InvalidConfig(
+                "Minimum reports must be between 1 and the number of nodes".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Summary of a network's reporting during a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OracleNetworkStatistics {
+    /// Number of nodes
+    pub nodes: usize,
+    /// Nodes that were not honest
+    pub faulty_nodes: usize,
+    /// Oracle updates
+    pub updates: usize,
+    /// Updates where too few fresh reports arrived and the last value was held
+    pub stale_updates: usize,
+    /// Average fresh reports per update
+    pub avg_reports: f64,
+    /// Average absolute gap between the published BSI and the latent belief
+    pub avg_abs_error: f64,
+}
+
+/// State of one node during a run
+#[derive(Debug, Clone)]
+struct NodeState {
+    node: OracleNode,
+    noise: Option<Normal<f64>>,
+    view: VecDeque<BSI>,
+    last_report: Option<(f64, usize)>,
+}
+
+/// Oracle network publishing an aggregated BSI
+#[derive(Debug, Clone)]
+pub struct OracleNetwork {
+    config: OracleNetworkConfig,
+    nodes: Vec<NodeState>,
+    published: Option<BSI>,
+    tick: usize,
+    statistics: OracleNetworkStatistics,
+    total_reports: usize,
+    total_error: f64,
+}
+
+impl OracleNetwork {
+    /// Create a network
+    pub fn new(config: OracleNetworkConfig) -> Result<Self> {
+        config.validate()?;
+        let nodes = config
+            .nodes
+            .iter()
+            .map(|&node| {
+                let noise = if node.noise > 0.0 {
+                    let normal = Normal::new(0.0, node.noise)
+                        .map_err(|e| SimulatorError::OracleError(e.to_string()))?;
+                    Some(normal)
+                } else {
+                    None
+                };
+                Ok(NodeState {
+                    node,
+                    noise,
+                    view: VecDeque::with_capacity(node.latency_ticks as usize + 1),
+                    last_report: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(OracleNetwork {
+            statistics: OracleNetworkStatistics {
+                nodes: config.nodes.len(),
+                faulty_nodes: config.faulty_nodes(),
+                ..OracleNetworkStatistics::default()
+            },
+            config,
+            nodes,
+            published: None,
+            tick: 0,
+            total_reports: 0,
+            total_error: 0.0,
+        })
+    }
+
+    /// Collect node reports on the latest latent belief and publish the aggregate
+    ///
+    /// When too few fresh reports are available the previous value is held;
+    /// before the first publication the latent belief itself is used.
+    pub fn observe<R: Rng + ?Sized>(&mut self, latent: BSI, rng: &mut R) -> Result<BSI> {
+        self.tick += 1;
+        for state in &mut self.nodes {
+            state.view.push_back(latent);
+            if state.view.len() > state.node.latency_ticks as usize + 1 {
+                state.view.pop_front();
+            }
+
+            if rng.gen_bool(state.node.uptime) {
+                let seen = state.view[0].value();
+                // Colluders report their agreed value exactly
+                let (mut report, noisy) = match state.node.behavior {
+                    NodeBehavior::Honest => (seen, true),
+                    NodeBehavior::Biased { offset } => (seen + offset, true),
+                    NodeBehavior::Colluding { target } => (target, false),
+                };
+                if let (Some(noise), true) = (&state.noise, noisy) {
+                    report += noise.sample(rng);
+                }
+                state.last_report = Some((report.clamp(0.0, 1.0), self.tick));
+            }
+        }
+
+        let max_age = self.config.max_staleness_ticks as usize;
+        let reports: Vec<(f64, f64)> = self
+            .nodes
+            .iter()
+            .filter_map(|state| {
+                let (value, tick) = state.last_report?;
+                (self.tick - tick <= max_age).then_some((value, state.node.stake))
+            })
+            .collect();
+
+        let published = match self.config.aggregator.aggregate(&reports) {
+            Some(value) if reports.len() >= self.config.min_reports => {
+                BSI::new(value.clamp(0.0, 1.0)).map_err(SimulatorError::OracleError)?
+            }
+            _ => {
+                self.statistics.stale_updates += 1;
+                self.published.unwrap_or(latent)
+            }
+        };
+        self.published = Some(published);
+
+        self.statistics.updates += 1;
+        self.total_reports += reports.len();
+        self.total_error += (published.value() - latent.value()).abs();
+        Ok(published)
+    }
+
+    /// Get network statistics
+    pub fn statistics(&self) -> OracleNetworkStatistics {
+        let updates = self.statistics.updates.max(1) as f64;
+        OracleNetworkStatistics {
+            avg_reports: self.total_reports as f64 / updates,
+            avg_abs_error: self.total_error / updates,
+            ..self.statistics
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::{RngStreams, OBSERVATION_STREAM};
+
+    #[test]
+    fn test_aggregators() {
+        let reports = [(0.1, 1.0), (0.5, 1.0), (0.6, 1.0), (0.7, 1.0), (0.9, 6.0)];
+
+        assert_eq!(Aggregator::Median.aggregate(&reports), Some(0.6));
+        let trimmed = Aggregator::TrimmedMean { trim_fraction: 0.2 }.aggregate(&reports);
+        assert!((trimmed.unwrap() - 0.6).abs() < 1e-12);
+        // The heavily staked node holds more than half of the stake
+        assert_eq!(Aggregator::StakeWeighted.aggregate(&reports), Some(0.9));
+        assert_eq!(Aggregator::Median.aggregate(&[]), None);
+    }
+
+    #[test]
+    fn test_colluding_minority_cannot_move_median() {
+        let mut rng = RngStreams::new(3).stream(OBSERVATION_STREAM);
+        let latent = BSI::new(0.5).unwrap();
+        let colluding = NodeBehavior::Colluding { target: 1.0 };
+
+        let mut minority =
+            OracleNetwork::new(OracleNetworkConfig::honest(7).with_adversaries(3, colluding))
+                .unwrap();
+        let mut majority =
+            OracleNetwork::new(OracleNetworkConfig::honest(7).with_adversaries(4, colluding))
+                .unwrap();
+
+        for _ in 0..20 {
+            assert!(minority.observe(latent, &mut rng).unwrap().value() < 0.6);
+        }
+        // Once every node has a fresh report, the colluders hold the median
+        for _ in 0..3 {
+            majority.observe(latent, &mut rng).unwrap();
+        }
+        assert_eq!(majority.observe(latent, &mut rng).unwrap().value(), 1.0);
+        assert_eq!(majority.statistics().faulty_nodes, 4);
+    }
+
+    #[test]
+    fn test_outages_hold_last_value() {
+        let mut rng = RngStreams::new(3).stream(OBSERVATION_STREAM);
+        let mut config = OracleNetworkConfig::honest(3);
+        config.nodes.iter_mut().for_each(|node| node.noise = 0.0);
+        let mut network = OracleNetwork::new(config.clone()).unwrap();
+        network.observe(BSI::new(0.4).unwrap(), &mut rng).unwrap();
+
+        // Every node goes down: reports age out and the last value is held
+        network
+            .nodes
+            .iter_mut()
+            .for_each(|state| state.node.uptime = 0.0);
+        let values: Vec<f64> = (0..5)
+            .map(|_| {
+                network
+                    .observe(BSI::new(0.8).unwrap(), &mut rng)
+                    .unwrap()
+                    .value()
+            })
+            .collect();
+
+        assert!(values.iter().all(|&value| value == 0.4));
+        assert_eq!(network.statistics().stale_updates, 2);
+    }
+}
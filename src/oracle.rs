@@ -1,13 +1,20 @@
 //! Oracle simulation for belief signal generation
 
+use crate::belief::{BeliefProcess, BeliefProcessConfig};
 use crate::error::{Result, SimulatorError};
+use crate::rng::SimRng;
 use crate::types::BSI;
 use rand::Rng;
 use rand_distr::{Distribution, Normal};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 /// Oracle simulator configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `process` selects the latent belief process; the noise, drift and mean
+/// reversion settings drive the default legacy process. The `observation_*`
+/// parameters describe how the oracle reports the belief.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OracleConfig {
     /// Base update frequency in seconds
     pub update_frequency: u32,
@@ -17,6 +24,18 @@ pub struct OracleConfig {
     pub drift_rate: f64,
     /// Mean reversion strength
     pub mean_reversion: f64,
+    /// Stochastic process driving the latent belief
+    #[serde(default)]
+    pub process: BeliefProcessConfig,
+    /// Standard deviation of noise added to each observation
+    #[serde(default)]
+    pub observation_noise: f64,
+    /// Updates by which observations lag the latent belief
+    #[serde(default)]
+    pub observation_delay: u32,
+    /// Constant offset added to each observation
+    #[serde(default)]
+    pub observation_bias: f64,
 }
 
 impl Default for OracleConfig {
@@ -26,25 +45,67 @@ impl Default for OracleConfig {
             noise_level: 0.05,
             drift_rate: 0.01,
             mean_reversion: 0.1,
+            process: BeliefProcessConfig::default(),
+            observation_noise: 0.0,
+            observation_delay: 0,
+            observation_bias: 0.0,
         }
     }
 }
 
+impl OracleConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if self.update_frequency == 0 {
+            return Err(SimulatorError::InvalidConfig(
+                "Oracle update frequency must be greater than 0".to_string(),
+            ));
+        }
+
+        let rates = [self.noise_level, self.drift_rate, self.mean_reversion];
+        if !rates.iter().all(|rate| (0.0..=1.0).contains(rate)) {
+            return Err(SimulatorError::InvalidConfig(
+                "Oracle noise, drift and mean reversion must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.observation_noise) {
+            return Err(SimulatorError::InvalidConfig(
+                "Observation noise must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if !(-1.0..=1.0).contains(&self.observation_bias) {
+            return Err(SimulatorError::InvalidConfig(
+                "Observation bias must be between -1.0 and 1.0".to_string(),
+            ));
+        }
+
+        self.process.validate()
+    }
+}
+
 /// Oracle simulator for generating belief signals
 #[derive(Debug)]
 pub struct OracleSimulator {
     config: OracleConfig,
+    process: Box<dyn BeliefProcess>,
     current_bsi: BSI,
     target_bsi: Option<f64>,
+    feedback: f64,
 }
 
 impl OracleSimulator {
     /// Create a new oracle simulator
     pub fn new(config: OracleConfig, initial_bsi: BSI) -> Self {
         OracleSimulator {
+            process: config
+                .process
+                .build(config.noise_level, config.drift_rate, config.mean_reversion),
             config,
             current_bsi: initial_bsi,
             target_bsi: None,
+            feedback: 0.0,
         }
     }
 
@@ -53,44 +114,51 @@ impl OracleSimulator {
         self.target_bsi = Some(target);
     }
 
-    /// Generate next BSI value
-    pub fn next_bsi(&mut self) -> Result<BSI> {
-        let mut rng = rand::thread_rng();
-        
-        // Base value
-        let mut next_value = self.current_bsi.value();
-
-        // Add drift
-        if let Some(target) = self.target_bsi {
-            // Drift toward target
-            let diff = target - next_value;
-            next_value += diff * self.config.drift_rate;
-        } else {
-            // Random walk
-            let drift = rng.gen_range(-self.config.drift_rate..self.config.drift_rate);
-            next_value += drift;
-        }
+    /// Stop drifting toward a target
+    pub fn clear_target(&mut self) {
+        self.target_bsi = None;
+    }
+
+    /// Scale the belief process volatility relative to the configuration
+    ///
+    /// The process is rebuilt, so stateful processes restart.
+    pub fn set_volatility_multiplier(&mut self, multiplier: f64) {
+        self.process = self.config.process.with_volatility_scaled(multiplier).build(
+            self.config.noise_level * multiplier,
+            self.config.drift_rate,
+            self.config.mean_reversion,
+        );
+    }
 
-        // Add noise
-        let normal = Normal::new(0.0, self.config.noise_level)
-            .map_err(|e| SimulatorError::OracleError(e.to_string()))?;
-        let noise = normal.sample(&mut rng);
-        next_value += noise;
+    /// Add a trading feedback adjustment to the next update
+    pub fn add_feedback(&mut self, adjustment: f64) {
+        self.feedback += adjustment;
+    }
 
-        // Mean reversion
-        let mean = 0.5;
-        let reversion = (mean - next_value) * self.config.mean_reversion;
-        next_value += reversion;
+    /// Generate next BSI value
+    ///
+    /// The belief process takes one step toward the scenario target, if any,
+    /// and feedback from trading since the last update is added.
+    pub fn next_bsi(&mut self, rng: &mut SimRng) -> Result<BSI> {
+        let mut next_value = self
+            .process
+            .step(self.current_bsi.value(), self.target_bsi, rng)?;
+        next_value += std::mem::take(&mut self.feedback);
 
         // Clamp to valid range
         next_value = next_value.clamp(0.0, 1.0);
 
         self.current_bsi = BSI::new(next_value)
-            .map_err(|e| SimulatorError::OracleError(e))?;
+            .map_err(SimulatorError::OracleError)?;
 
         Ok(self.current_bsi)
     }
 
+    /// Name of the belief process
+    pub fn process_name(&self) -> &'static str {
+        self.process.name()
+    }
+
     /// Get current BSI
     pub fn current_bsi(&self) -> BSI {
         self.current_bsi
@@ -102,7 +170,7 @@ impl OracleSimulator {
         new_value = new_value.clamp(0.0, 1.0);
         
         self.current_bsi = BSI::new(new_value)
-            .map_err(|e| SimulatorError::OracleError(e))?;
+            .map_err(SimulatorError::OracleError)?;
 
         Ok(self.current_bsi)
     }
@@ -111,12 +179,65 @@ impl OracleSimulator {
     pub fn reset(&mut self, initial_bsi: BSI) {
         self.current_bsi = initial_bsi;
         self.target_bsi = None;
+        self.feedback = 0.0;
+        self.process = self.config.process.build(
+            self.config.noise_level,
+            self.config.drift_rate,
+            self.config.mean_reversion,
+        );
+    }
+}
+
+/// Noisy, delayed and possibly biased view of the latent belief
+#[derive(Debug, Clone)]
+pub struct OracleObserver {
+    noise: Option<Normal<f64>>,
+    bias: f64,
+    delay: usize,
+    history: VecDeque<BSI>,
+}
+
+impl OracleObserver {
+    /// Create an observer for the observation parameters of `config`
+    pub fn new(config: &OracleConfig) -> Result<Self> {
+        let noise = if config.observation_noise > 0.0 {
+            let normal = Normal::new(0.0, config.observation_noise)
+                .map_err(|e| SimulatorError::OracleError(e.to_string()))?;
+            Some(normal)
+        } else {
+            None
+        };
+
+        Ok(OracleObserver {
+            noise,
+            bias: config.observation_bias,
+            delay: config.observation_delay as usize,
+            history: VecDeque::with_capacity(config.observation_delay as usize + 1),
+        })
+    }
+
+    /// Report an observation of the latest latent belief
+    ///
+    /// Until `delay` updates have been seen, the earliest belief is reported.
+    pub fn observe<R: Rng + ?Sized>(&mut self, latent: BSI, rng: &mut R) -> Result<BSI> {
+        self.history.push_back(latent);
+        if self.history.len() > self.delay + 1 {
+            self.history.pop_front();
+        }
+
+        let mut value = self.history[0].value() + self.bias;
+        if let Some(noise) = &self.noise {
+            value += noise.sample(rng);
+        }
+
+        BSI::new(value.clamp(0.0, 1.0)).map_err(SimulatorError::OracleError)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::{RngStreams, ORACLE_STREAM};
 
     #[test]
     fn test_oracle_creation() {
@@ -132,8 +253,9 @@ mod tests {
         let config = OracleConfig::default();
         let initial_bsi = BSI::new(0.5).unwrap();
         let mut oracle = OracleSimulator::new(config, initial_bsi);
+        let mut rng = RngStreams::new(7).stream(ORACLE_STREAM);
 
-        let next = oracle.next_bsi().unwrap();
+        let next = oracle.next_bsi(&mut rng).unwrap();
         assert!(next.value() >= 0.0 && next.value() <= 1.0);
     }
 
@@ -155,14 +277,34 @@ mod tests {
         };
         let initial_bsi = BSI::new(0.3).unwrap();
         let mut oracle = OracleSimulator::new(config, initial_bsi);
-        
+        let mut rng = RngStreams::new(7).stream(ORACLE_STREAM);
+
         oracle.set_target(0.7);
-        
+
         // After several updates, should drift toward target
         for _ in 0..10 {
-            oracle.next_bsi().unwrap();
+            oracle.next_bsi(&mut rng).unwrap();
         }
         
         assert!(oracle.current_bsi().value() > 0.3);
     }
+
+    #[test]
+    fn test_observer_delay_and_bias() {
+        let config = OracleConfig {
+            observation_delay: 2,
+            observation_bias: 0.1,
+            ..Default::default()
+        };
+        let mut observer = OracleObserver::new(&config).unwrap();
+        let mut rng = RngStreams::new(7).stream(ORACLE_STREAM);
+
+        let observed: Vec<f64> = [0.2, 0.3, 0.4, 0.5, 0.95]
+            .iter()
+            .map(|&v| observer.observe(BSI::new(v).unwrap(), &mut rng).unwrap().value())
+            .collect();
+
+        let expected = [0.3, 0.3, 0.3, 0.4, 0.5];
+        assert!(observed.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-12));
+    }
 }
@@ -0,0 +1,512 @@
+//! Central limit order book with price-time priority matching
+//!
+//! The book trades YES contracts. Buying YES opens or increases a long
+//! position; selling YES reduces a long or opens a short (NO) position.
+
+use crate::error::{Result, SimulatorError};
+use crate::types::PositionType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Minimum price increment
+pub const PRICE_TICK: f64 = 0.001;
+
+/// Number of ticks in a full unit of price
+const TICKS_PER_UNIT: u32 = 1000;
+
+/// Order book venue configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookConfig {
+    /// Probability that a participant posts a limit order instead of a market order
+    pub limit_order_probability: f64,
+    /// Maximum distance of limit prices from the BSI
+    pub max_price_offset: f64,
+    /// Number of simulation ticks a resting order lives before expiring
+    pub order_ttl_ticks: u32,
+}
+
+impl Default for OrderBookConfig {
+    fn default() -> Self {
+        OrderBookConfig {
+            limit_order_probability: 0.7,
+            max_price_offset: 0.02,
+            order_ttl_ticks: 12,
+        }
+    }
+}
+
+impl OrderBookConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.limit_order_probability) {
+            return Err(SimulatorError::InvalidConfig(
+                "Limit order probability must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if !(0.0..1.0).contains(&self.max_price_offset) {
+            return Err(SimulatorError::InvalidConfig(
+                "Limit price offset must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if self.order_ttl_ticks == 0 {
+            return Err(SimulatorError::InvalidConfig(
+                "Order time-to-live must be at least 1 tick".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// How participant orders reach the market
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum VenueConfig {
+    /// Fill instantly against the pricing mechanism
+    #[default]
+    Instant,
+    /// Match participant orders on a central limit order book
+    OrderBook(OrderBookConfig),
+}
+
+impl VenueConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            VenueConfig::Instant => Ok(()),
+            VenueConfig::OrderBook(config) => config.validate(),
+        }
+    }
+}
+
+/// Side of an order on YES contracts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderSide {
+    /// Buy YES contracts
+    Buy,
+    /// Sell YES contracts
+    Sell,
+}
+
+impl OrderSide {
+    /// Position side an order on this book side moves toward
+    pub fn position_type(&self) -> PositionType {
+        match self {
+            OrderSide::Buy => PositionType::Long,
+            OrderSide::Sell => PositionType::Short,
+        }
+    }
+
+    /// Order side that moves a position toward `position_type`
+    pub fn for_position(position_type: PositionType) -> Self {
+        match position_type {
+            PositionType::Long => OrderSide::Buy,
+            PositionType::Short => OrderSide::Sell,
+        }
+    }
+}
+
+/// An order resting on the book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    /// Order ID
+    pub id: u64,
+    /// Participant ID
+    pub participant_id: String,
+    /// Book side
+    pub side: OrderSide,
+    /// Limit price (None for market orders)
+    pub limit_price: Option<f64>,
+    /// Original size
+    pub size: f64,
+    /// Unfilled size
+    pub remaining: f64,
+    /// Submission time
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A single match between a resting and an incoming order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Execution {
+    /// Resting order ID
+    pub maker_order_id: u64,
+    /// Incoming order ID
+    pub taker_order_id: u64,
+    /// Participant owning the resting order
+    pub maker_participant_id: String,
+    /// Participant owning the incoming order
+    pub taker_participant_id: String,
+    /// Book side of the incoming order
+    pub taker_side: OrderSide,
+    /// Fill price (the resting order's price)
+    pub price: f64,
+    /// Filled size
+    pub size: f64,
+    /// Whether the resting order still has size left
+    pub maker_partial: bool,
+    /// Whether the incoming order still has size left
+    pub taker_partial: bool,
+}
+
+/// Result of submitting an order
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderResult {
+    /// Assigned order ID
+    pub order_id: u64,
+    /// Matches in execution order
+    pub executions: Vec<Execution>,
+    /// Size left resting on the book
+    pub resting: f64,
+}
+
+/// Order book spread, depth and queue statistics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookStatistics {
+    /// Orders submitted
+    pub orders_submitted: usize,
+    /// Market orders submitted
+    pub market_orders: usize,
+    /// Orders filled completely
+    pub orders_filled: usize,
+    /// Executions that left an order partially filled
+    pub partial_fills: usize,
+    /// Orders cancelled, including unfilled market order remainders
+    pub orders_cancelled: usize,
+    /// Resting orders that reached their time-to-live
+    pub orders_expired: usize,
+    /// Total matches
+    pub executions: usize,
+    /// Average bid-ask spread over samples where both sides were quoted
+    pub avg_spread: Option<f64>,
+    /// Average resting bid size
+    pub avg_bid_depth: f64,
+    /// Average resting ask size
+    pub avg_ask_depth: f64,
+    /// Average number of resting orders
+    pub avg_queue_length: f64,
+}
+
+/// Price-time priority limit order book
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrderBook {
+    bids: BTreeMap<u32, VecDeque<Order>>,
+    asks: BTreeMap<u32, VecDeque<Order>>,
+    next_order_id: u64,
+    last_price: Option<f64>,
+    stats: OrderBookStatistics,
+    spread_sum: f64,
+    spread_samples: usize,
+    depth_sums: (f64, f64),
+    queue_sum: f64,
+    samples: usize,
+}
+
+fn to_ticks(price: f64) -> u32 {
+    ((price * TICKS_PER_UNIT as f64).round() as u32).clamp(1, TICKS_PER_UNIT - 1)
+}
+
+fn to_price(ticks: u32) -> f64 {
+    ticks as f64 * PRICE_TICK
+}
+
+impl OrderBook {
+    /// Create an empty book
+    pub fn new() -> Self {
+        OrderBook::default()
+    }
+
+    /// Best bid price
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.keys().next_back().map(|&t| to_price(t))
+    }
+
+    /// Best ask price
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.keys().next().map(|&t| to_price(t))
+    }
+
+    /// Bid-ask spread when both sides are quoted
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+
+    /// Midpoint when both sides are quoted, otherwise the last trade price
+    pub fn reference_price(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+            _ => self.last_price,
+        }
+    }
+
+    /// Total resting size on each side as `(bids, asks)`
+    pub fn depth(&self) -> (f64, f64) {
+        let total = |levels: &BTreeMap<u32, VecDeque<Order>>| {
+            levels.values().flatten().map(|o| o.remaining).sum::<f64>()
+        };
+        (total(&self.bids), total(&self.asks))
+    }
+
+    /// Number of resting orders
+    pub fn queue_length(&self) -> usize {
+        self.bids
+            .values()
+            .chain(self.asks.values())
+            .map(VecDeque::len)
+            .sum()
+    }
+
+    /// Submit a limit (`Some(price)`) or market (`None`) order and match it
+    pub fn submit(
+        &mut self,
+        participant_id: &str,
+        side: OrderSide,
+        limit_price: Option<f64>,
+        size: f64,
+        timestamp: DateTime<Utc>,
+    ) -> OrderResult {
+        self.next_order_id += 1;
+        let order_id = self.next_order_id;
+        self.stats.orders_submitted += 1;
+        if limit_price.is_none() {
+            self.stats.market_orders += 1;
+        }
+
+        let limit_ticks = limit_price.map(to_ticks);
+        let mut remaining = size;
+        let mut executions = Vec::new();
+
+        while remaining > f64::EPSILON {
+            let level = match side {
+                OrderSide::Buy => self.asks.first_entry(),
+                OrderSide::Sell => self.bids.last_entry(),
+            };
+            let Some(mut level) = level else {
+                break;
+            };
+
+            let ticks = *level.key();
+            let crosses = match (side, limit_ticks) {
+                (_, None) => true,
+                (OrderSide::Buy, Some(limit)) => ticks <= limit,
+                (OrderSide::Sell, Some(limit)) => ticks >= limit,
+            };
+            if !crosses {
+                break;
+            }
+
+            let queue = level.get_mut();
+            let maker = queue.front_mut().expect("price levels are never empty");
+            if maker.participant_id == participant_id {
+                // Self-trade prevention: cancel the resting order
+                queue.pop_front();
+                self.stats.orders_cancelled += 1;
+            } else {
+                let filled = remaining.min(maker.remaining);
+                maker.remaining -= filled;
+                remaining -= filled;
+
+                let maker_done = maker.remaining <= f64::EPSILON;
+                let taker_partial = remaining > f64::EPSILON;
+                executions.push(Execution {
+                    maker_order_id: maker.id,
+                    taker_order_id: order_id,
+                    maker_participant_id: maker.participant_id.clone(),
+                    taker_participant_id: participant_id.to_string(),
+                    taker_side: side,
+                    price: to_price(ticks),
+                    size: filled,
+                    maker_partial: !maker_done,
+                    taker_partial,
+                });
+                self.stats.executions += 1;
+                if !maker_done || taker_partial {
+                    self.stats.partial_fills += 1;
+                }
+                if maker_done {
+                    queue.pop_front();
+                    self.stats.orders_filled += 1;
+                }
+                self.last_price = Some(to_price(ticks));
+            }
+
+            if queue.is_empty() {
+                level.remove();
+            }
+        }
+
+        if remaining <= f64::EPSILON {
+            self.stats.orders_filled += 1;
+            remaining = 0.0;
+        }
+
+        let resting = match limit_ticks {
+            Some(ticks) if remaining > 0.0 => {
+                let book = match side {
+                    OrderSide::Buy => &mut self.bids,
+                    OrderSide::Sell => &mut self.asks,
+                };
+                book.entry(ticks).or_default().push_back(Order {
+                    id: order_id,
+                    participant_id: participant_id.to_string(),
+                    side,
+                    limit_price: Some(to_price(ticks)),
+                    size,
+                    remaining,
+                    timestamp,
+                });
+                remaining
+            }
+            _ => {
+                if remaining > 0.0 {
+                    // Unfilled market order remainder is cancelled
+                    self.stats.orders_cancelled += 1;
+                }
+                0.0
+            }
+        };
+
+        OrderResult {
+            order_id,
+            executions,
+            resting,
+        }
+    }
+
+    /// Cancel all resting orders of a participant
+    pub fn cancel_participant(&mut self, participant_id: &str) -> usize {
+        let cancelled = self.remove_where(|order| order.participant_id == participant_id);
+        self.stats.orders_cancelled += cancelled;
+        cancelled
+    }
+
+    /// Expire resting orders submitted before `cutoff`
+    pub fn expire_before(&mut self, cutoff: DateTime<Utc>) -> usize {
+        let expired = self.remove_where(|order| order.timestamp < cutoff);
+        self.stats.orders_expired += expired;
+        expired
+    }
+
+    /// Cancel every resting order
+    pub fn clear(&mut self) -> usize {
+        let cancelled = self.remove_where(|_| true);
+        self.stats.orders_cancelled += cancelled;
+        cancelled
+    }
+
+    fn remove_where(&mut self, predicate: impl Fn(&Order) -> bool) -> usize {
+        let mut removed = 0;
+        for book in [&mut self.bids, &mut self.asks] {
+            for queue in book.values_mut() {
+                let before = queue.len();
+                queue.retain(|order| !predicate(order));
+                removed += before - queue.len();
+            }
+            book.retain(|_, queue| !queue.is_empty());
+        }
+        removed
+    }
+
+    /// Record the current spread, depth and queue length
+    pub fn sample(&mut self) {
+        if let Some(spread) = self.spread() {
+            self.spread_sum += spread;
+            self.spread_samples += 1;
+        }
+        let (bids, asks) = self.depth();
+        self.depth_sums.0 += bids;
+        self.depth_sums.1 += asks;
+        self.queue_sum += self.queue_length() as f64;
+        self.samples += 1;
+    }
+
+    /// Get accumulated statistics
+    pub fn statistics(&self) -> OrderBookStatistics {
+        let samples = self.samples.max(1) as f64;
+        OrderBookStatistics {
+            avg_spread: (self.spread_samples > 0)
+                .then(|| self.spread_sum / self.spread_samples as f64),
+            avg_bid_depth: self.depth_sums.0 / samples,
+            avg_ask_depth: self.depth_sums.1 / samples,
+            avg_queue_length: self.queue_sum / samples,
+            ..self.stats.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+    use chrono::Duration;
+
+    #[test]
+    fn test_price_time_priority_and_partial_fills() {
+        let mut book = OrderBook::new();
+        let t0 = default_start_time();
+
+        book.submit("a", OrderSide::Sell, Some(0.52), 10.0, t0);
+        book.submit("b", OrderSide::Sell, Some(0.51), 5.0, t0);
+        book.submit(
+            "c",
+            OrderSide::Sell,
+            Some(0.51),
+            5.0,
+            t0 + Duration::minutes(1),
+        );
+        book.submit("d", OrderSide::Buy, Some(0.48), 8.0, t0);
+        assert!((book.spread().unwrap() - 0.03).abs() < 1e-9);
+
+        // Best price first, then earliest order at that price
+        let result = book.submit("e", OrderSide::Buy, Some(0.52), 12.0, t0);
+        let makers: Vec<&str> = result
+            .executions
+            .iter()
+            .map(|e| e.maker_participant_id.as_str())
+            .collect();
+        assert_eq!(makers, vec!["b", "c", "a"]);
+        assert!((result.executions[2].price - 0.52).abs() < 1e-9);
+        assert!(result.executions[2].maker_partial);
+        assert_eq!(result.resting, 0.0);
+
+        let (bids, asks) = book.depth();
+        assert!((bids - 8.0).abs() < 1e-9);
+        assert!((asks - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_market_order_remainder_cancelled() {
+        let mut book = OrderBook::new();
+        let t0 = default_start_time();
+
+        book.submit("a", OrderSide::Buy, Some(0.40), 3.0, t0);
+        let result = book.submit("b", OrderSide::Sell, None, 5.0, t0);
+
+        assert_eq!(result.executions.len(), 1);
+        assert!(result.executions[0].taker_partial);
+        assert_eq!(result.resting, 0.0);
+        assert_eq!(book.queue_length(), 0);
+        assert_eq!(book.statistics().orders_cancelled, 1);
+    }
+
+    #[test]
+    fn test_expire_resting_orders() {
+        let mut book = OrderBook::new();
+        let t0 = default_start_time();
+
+        book.submit("a", OrderSide::Buy, Some(0.40), 3.0, t0);
+        book.submit(
+            "b",
+            OrderSide::Buy,
+            Some(0.41),
+            3.0,
+            t0 + Duration::hours(1),
+        );
+        book.sample();
+
+        assert_eq!(book.expire_before(t0 + Duration::minutes(30)), 1);
+        assert!((book.best_bid().unwrap() - 0.41).abs() < 1e-9);
+        assert!((book.statistics().avg_bid_depth - 6.0).abs() < 1e-9);
+    }
+}
@@ -1,6 +1,7 @@
 //! Market participant simulation
 
-use crate::types::{BSI, Position, PositionType};
+use crate::types::{BSI, Position, PositionType, TradeType};
+use chrono::{DateTime, Utc};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
@@ -9,34 +10,253 @@ use serde::{Deserialize, Serialize};
 pub struct Participant {
     /// Unique participant ID
     pub id: String,
-    /// Participant behavior type
+    /// Behavior model traded by the built-in agent
     pub behavior: ParticipantBehavior,
-    /// Current positions
+    /// Current positions (at most one net position per market)
     pub positions: Vec<Position>,
     /// Total capital
     pub capital: f64,
+    /// Capital at creation
+    pub initial_capital: f64,
     /// Risk tolerance (0.0 to 1.0)
     pub risk_tolerance: f64,
+    /// Profit and loss realized from closed contracts
+    pub realized_pnl: f64,
+    /// Trading and settlement fees paid
+    pub fees_paid: f64,
 }
 
+/// Change to a participant's position produced by one fill
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionChange {
+    /// Lifecycle step
+    pub trade_type: TradeType,
+    /// Side of the position that changed
+    pub position_type: PositionType,
+    /// Contracts affected
+    pub size: f64,
+    /// Profit and loss realized by this change
+    pub realized_pnl: f64,
+}
+
+/// Tolerance below which a remaining position size counts as closed
+const SIZE_EPSILON: f64 = 1e-9;
+
 impl Participant {
     /// Create a new participant
-    pub fn new(id: String, behavior: ParticipantBehavior, capital: f64) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn new<R: Rng + ?Sized>(
+        id: String,
+        behavior: ParticipantBehavior,
+        capital: f64,
+        rng: &mut R,
+    ) -> Self {
+        Self::with_risk_tolerance(id, behavior, capital, rng.gen_range(0.1..0.9))
+    }
+
+    /// Create a participant with a fixed risk tolerance
+    pub fn with_risk_tolerance(
+        id: String,
+        behavior: ParticipantBehavior,
+        capital: f64,
+        risk_tolerance: f64,
+    ) -> Self {
         Participant {
             id,
             behavior,
             positions: Vec::new(),
             capital,
-            risk_tolerance: rng.gen_range(0.1..0.9),
+            initial_capital: capital,
+            risk_tolerance,
+            realized_pnl: 0.0,
+            fees_paid: 0.0,
+        }
+    }
+
+    /// Pay a fee out of capital
+    pub fn pay_fee(&mut self, fee: f64) {
+        self.capital -= fee;
+        self.fees_paid += fee;
+    }
+
+    /// Get the current net position, if any
+    pub fn position(&self) -> Option<&Position> {
+        self.positions.first()
+    }
+
+    /// Largest part of an order for `size` contracts of `side` the account can pay for
+    ///
+    /// Contracts that reduce an opposite position pay out and are always
+    /// affordable; the opening remainder is limited to capital at `unit_cost`
+    /// per contract.
+    pub fn affordable_size(&self, side: PositionType, size: f64, unit_cost: f64) -> f64 {
+        let reducible = self
+            .position()
+            .filter(|position| position.position_type != side)
+            .map_or(0.0, |position| position.size);
+        size.min(reducible + self.capital.max(0.0) / unit_cost)
+    }
+
+    /// Net position in contracts (positive = long, negative = short)
+    pub fn net_position(&self) -> f64 {
+        self.position().map_or(0.0, |position| match position.position_type {
+            PositionType::Long => position.size,
+            PositionType::Short => -position.size,
+        })
+    }
+
+    /// Apply a fill of `size` contracts on `side` at the given YES price
+    ///
+    /// Fills against an existing position on the opposite side net it down
+    /// first; any remainder opens a new position on `side`.
+    pub fn apply_fill(
+        &mut self,
+        side: PositionType,
+        size: f64,
+        price: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Vec<PositionChange> {
+        let mut changes = Vec::new();
+        let mut remaining = size;
+
+        if let Some(position) = self.positions.first_mut() {
+            if position.position_type == side {
+                // Same side: average into the existing position
+                let total = position.size + remaining;
+                position.entry_price =
+                    (position.entry_price * position.size + price * remaining) / total;
+                position.size = total;
+                self.capital -= remaining * side.unit_price(price);
+                changes.push(PositionChange {
+                    trade_type: TradeType::Increase,
+                    position_type: side,
+                    size: remaining,
+                    realized_pnl: 0.0,
+                });
+                return changes;
+            }
+
+            // Opposite side: reduce the existing position
+            let held = position.position_type;
+            let reduced = remaining.min(position.size);
+            let proceeds = reduced * held.unit_price(price);
+            let realized = proceeds - reduced * held.unit_price(position.entry_price);
+            position.size -= reduced;
+            remaining -= reduced;
+            self.capital += proceeds;
+            self.realized_pnl += realized;
+
+            let closed = position.size <= SIZE_EPSILON;
+            changes.push(PositionChange {
+                trade_type: if closed { TradeType::Close } else { TradeType::Decrease },
+                position_type: held,
+                size: reduced,
+                realized_pnl: realized,
+            });
+            if closed {
+                self.positions.clear();
+            }
+        }
+
+        if remaining > SIZE_EPSILON {
+            self.capital -= remaining * side.unit_price(price);
+            self.positions.push(Position {
+                participant_id: self.id.clone(),
+                size: remaining,
+                entry_price: price,
+                entry_time: timestamp,
+                position_type: side,
+            });
+            changes.push(PositionChange {
+                trade_type: TradeType::Open,
+                position_type: side,
+                size: remaining,
+                realized_pnl: 0.0,
+            });
         }
+
+        changes
+    }
+
+    /// Unrealized profit and loss at the given YES price
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        self.positions.iter().map(|p| p.unrealized_pnl(price)).sum()
+    }
+
+    /// Capital plus open positions marked at the given YES price
+    pub fn equity(&self, price: f64) -> f64 {
+        self.capital + self.positions.iter().map(|p| p.mark_value(price)).sum::<f64>()
+    }
+
+    /// Decide whether to trade based on current BSI
+    pub fn should_trade<R: Rng + ?Sized>(
+        &self,
+        current_bsi: BSI,
+        threshold: f64,
+        rng: &mut R,
+    ) -> bool {
+        self.behavior.should_trade(current_bsi, threshold, rng)
+    }
+
+    /// Determine position type based on behavior and market state
+    pub fn determine_position_type<R: Rng + ?Sized>(
+        &self,
+        current_bsi: BSI,
+        threshold: f64,
+        rng: &mut R,
+    ) -> PositionType {
+        self.behavior.position_type(current_bsi, threshold, rng)
+    }
+
+    /// Calculate position size based on capital and risk tolerance
+    pub fn calculate_position_size(&self) -> f64 {
+        self.capital * self.risk_tolerance * 0.1
+    }
+}
+
+/// Participant behavior types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ParticipantBehavior {
+    /// Makes rational decisions based on BSI distance from threshold
+    Rational,
+    /// Follows momentum and trends
+    Momentum,
+    /// Trades against the trend
+    Contrarian,
+    /// Random trading decisions
+    Random,
+    /// Conservative, low-frequency trading
+    Conservative,
+    /// Aggressive, high-frequency trading
+    Aggressive,
+}
+
+impl ParticipantBehavior {
+    /// Get all behavior types
+    pub fn all() -> Vec<ParticipantBehavior> {
+        vec![
+            ParticipantBehavior::Rational,
+            ParticipantBehavior::Momentum,
+            ParticipantBehavior::Contrarian,
+            ParticipantBehavior::Random,
+            ParticipantBehavior::Conservative,
+            ParticipantBehavior::Aggressive,
+        ]
+    }
+
+    /// Get random behavior type
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let behaviors = Self::all();
+        behaviors[rng.gen_range(0..behaviors.len())]
     }
 
     /// Decide whether to trade based on current BSI
-    pub fn should_trade(&self, current_bsi: BSI, threshold: f64) -> bool {
-        let mut rng = rand::thread_rng();
-        
-        match self.behavior {
+    pub fn should_trade<R: Rng + ?Sized>(
+        &self,
+        current_bsi: BSI,
+        threshold: f64,
+        rng: &mut R,
+    ) -> bool {
+        match self {
             ParticipantBehavior::Rational => {
                 // Trade based on distance from threshold
                 let distance = current_bsi.distance_from(threshold);
@@ -66,9 +286,14 @@ impl Participant {
         }
     }
 
-    /// Determine position type based on behavior and market state
-    pub fn determine_position_type(&self, current_bsi: BSI, threshold: f64) -> PositionType {
-        match self.behavior {
+    /// Side to trade based on market state
+    pub fn position_type<R: Rng + ?Sized>(
+        &self,
+        current_bsi: BSI,
+        threshold: f64,
+        rng: &mut R,
+    ) -> PositionType {
+        match self {
             ParticipantBehavior::Rational => {
                 if current_bsi.value() < threshold {
                     PositionType::Long
@@ -91,7 +316,7 @@ impl Participant {
                 }
             }
             ParticipantBehavior::Random => {
-                if rand::thread_rng().gen_bool(0.5) {
+                if rng.gen_bool(0.5) {
                     PositionType::Long
                 } else {
                     PositionType::Short
@@ -106,54 +331,12 @@ impl Participant {
             }
         }
     }
-
-    /// Calculate position size based on capital and risk tolerance
-    pub fn calculate_position_size(&self) -> f64 {
-        self.capital * self.risk_tolerance * 0.1
-    }
-}
-
-/// Participant behavior types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ParticipantBehavior {
-    /// Makes rational decisions based on BSI distance from threshold
-    Rational,
-    /// Follows momentum and trends
-    Momentum,
-    /// Trades against the trend
-    Contrarian,
-    /// Random trading decisions
-    Random,
-    /// Conservative, low-frequency trading
-    Conservative,
-    /// Aggressive, high-frequency trading
-    Aggressive,
-}
-
-impl ParticipantBehavior {
-    /// Get all behavior types
-    pub fn all() -> Vec<ParticipantBehavior> {
-        vec![
-            ParticipantBehavior::Rational,
-            ParticipantBehavior::Momentum,
-            ParticipantBehavior::Contrarian,
-            ParticipantBehavior::Random,
-            ParticipantBehavior::Conservative,
-            ParticipantBehavior::Aggressive,
-        ]
-    }
-
-    /// Get random behavior type
-    pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
-        let behaviors = Self::all();
-        behaviors[rng.gen_range(0..behaviors.len())]
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::{RngStreams, PARTICIPANT_STREAM};
 
     #[test]
     fn test_participant_creation() {
@@ -161,6 +344,7 @@ mod tests {
             "test-1".to_string(),
             ParticipantBehavior::Rational,
             1000.0,
+            &mut RngStreams::new(1).stream(PARTICIPANT_STREAM),
         );
 
         assert_eq!(participant.id, "test-1");
@@ -174,10 +358,41 @@ mod tests {
             "test-1".to_string(),
             ParticipantBehavior::Rational,
             1000.0,
+            &mut RngStreams::new(1).stream(PARTICIPANT_STREAM),
         );
 
         let size = participant.calculate_position_size();
         assert!(size > 0.0);
         assert!(size <= participant.capital);
     }
+
+    #[test]
+    fn test_position_lifecycle() {
+        let mut participant = Participant::new(
+            "test-1".to_string(),
+            ParticipantBehavior::Rational,
+            1000.0,
+            &mut RngStreams::new(1).stream(PARTICIPANT_STREAM),
+        );
+        let now = crate::clock::default_start_time();
+
+        let open = participant.apply_fill(PositionType::Long, 100.0, 0.4, now);
+        assert_eq!(open[0].trade_type, TradeType::Open);
+        assert!((participant.capital - 960.0).abs() < 1e-9);
+
+        let increase = participant.apply_fill(PositionType::Long, 100.0, 0.6, now);
+        assert_eq!(increase[0].trade_type, TradeType::Increase);
+        assert!((participant.position().unwrap().entry_price - 0.5).abs() < 1e-9);
+
+        let decrease = participant.apply_fill(PositionType::Short, 50.0, 0.7, now);
+        assert_eq!(decrease[0].trade_type, TradeType::Decrease);
+        assert!((participant.realized_pnl - 10.0).abs() < 1e-9);
+
+        // Flip: close the remaining 150 long and open 50 short
+        let flip = participant.apply_fill(PositionType::Short, 200.0, 0.7, now);
+        assert_eq!(flip[0].trade_type, TradeType::Close);
+        assert_eq!(flip[1].trade_type, TradeType::Open);
+        assert_eq!(participant.position().unwrap().position_type, PositionType::Short);
+        assert!((participant.equity(0.7) - (1000.0 + 40.0)).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,377 @@
+//! Market pricing mechanisms
+//!
+//! Prices are quoted as the YES (long) price in `[0, 1]`. A short position
+//! holds NO contracts, each costing `1 - price`.
+
+use crate::error::{Result, SimulatorError};
+use crate::types::{PositionType, BSI};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Bounds for initial AMM prices so the pool starts with finite reserves
+const MIN_INITIAL_PRICE: f64 = 0.01;
+const MAX_INITIAL_PRICE: f64 = 0.99;
+
+/// Result of executing a trade against a pricing mechanism
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    /// Side of the contracts traded
+    pub side: PositionType,
+    /// Contracts bought (positive) or sold (negative)
+    pub size: f64,
+    /// Collateral paid by the trader (negative when receiving)
+    pub cost: f64,
+    /// Average execution price, quoted as the YES price
+    pub average_price: f64,
+    /// YES price before the trade
+    pub price_before: f64,
+    /// YES price after the trade
+    pub price_after: f64,
+    /// Extra collateral paid versus executing at the pre-trade price
+    pub slippage: f64,
+}
+
+impl Fill {
+    fn new(side: PositionType, size: f64, cost: f64, price_before: f64, price_after: f64) -> Self {
+        let unit_cost = if size != 0.0 { cost / size } else { side.unit_price(price_before) };
+        Fill {
+            side,
+            size,
+            cost,
+            average_price: side.unit_price(unit_cost),
+            price_before,
+            price_after,
+            slippage: cost - size * side.unit_price(price_before),
+        }
+    }
+}
+
+/// Determines the execution price of trades and how trades move the price
+pub trait PricingMechanism: Debug + Send + Sync {
+    /// Short mechanism name used in reports
+    fn name(&self) -> &'static str;
+
+    /// Current YES price
+    fn price(&self) -> f64;
+
+    /// Collateral cost of buying (positive) or selling (negative) `size` contracts of `side`
+    fn quote(&self, side: PositionType, size: f64) -> Result<f64>;
+
+    /// Execute a trade and move the price
+    fn execute(&mut self, side: PositionType, size: f64) -> Result<Fill>;
+
+    /// React to a new oracle value
+    fn on_oracle_update(&mut self, _bsi: BSI) {}
+
+    /// Maximum loss the liquidity provider can incur (None = unbounded)
+    fn worst_case_loss(&self) -> Option<f64>;
+
+    /// Clone into a boxed trait object
+    fn clone_box(&self) -> Box<dyn PricingMechanism>;
+}
+
+impl Clone for Box<dyn PricingMechanism> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+fn check_finite(cost: f64) -> Result<f64> {
+    if cost.is_finite() {
+        Ok(cost)
+    } else {
+        Err(SimulatorError::InvalidMarketState(
+            "pricing produced a non-finite cost".to_string(),
+        ))
+    }
+}
+
+/// Instant fills at the oracle BSI with no price impact
+#[derive(Debug, Clone)]
+pub struct OraclePricing {
+    price: f64,
+}
+
+impl OraclePricing {
+    /// Create oracle pricing at the given BSI
+    pub fn new(bsi: BSI) -> Self {
+        OraclePricing { price: bsi.value() }
+    }
+}
+
+impl PricingMechanism for OraclePricing {
+    fn name(&self) -> &'static str {
+        "oracle"
+    }
+
+    fn price(&self) -> f64 {
+        self.price
+    }
+
+    fn quote(&self, side: PositionType, size: f64) -> Result<f64> {
+        Ok(size * side.unit_price(self.price))
+    }
+
+    fn execute(&mut self, side: PositionType, size: f64) -> Result<Fill> {
+        let cost = self.quote(side, size)?;
+        Ok(Fill::new(side, size, cost, self.price, self.price))
+    }
+
+    fn on_oracle_update(&mut self, bsi: BSI) {
+        self.price = bsi.value();
+    }
+
+    fn worst_case_loss(&self) -> Option<f64> {
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn PricingMechanism> {
+        Box::new(self.clone())
+    }
+}
+
+/// Logarithmic market scoring rule market maker
+#[derive(Debug, Clone)]
+pub struct Lmsr {
+    liquidity: f64,
+    q_yes: f64,
+    q_no: f64,
+    initial_price: f64,
+}
+
+impl Lmsr {
+    /// Create an LMSR market maker with liquidity parameter `b` starting at `initial_price`
+    pub fn new(liquidity: f64, initial_price: f64) -> Self {
+        let p = initial_price.clamp(MIN_INITIAL_PRICE, MAX_INITIAL_PRICE);
+        Lmsr {
+            liquidity,
+            q_yes: liquidity * (p / (1.0 - p)).ln(),
+            q_no: 0.0,
+            initial_price: p,
+        }
+    }
+
+    /// Cost function `b * ln(exp(q_yes / b) + exp(q_no / b))`
+    fn cost(&self, q_yes: f64, q_no: f64) -> f64 {
+        let a = q_yes / self.liquidity;
+        let b = q_no / self.liquidity;
+        let max = a.max(b);
+        self.liquidity * (max + ((a - max).exp() + (b - max).exp()).ln())
+    }
+
+    fn shifted(&self, side: PositionType, size: f64) -> (f64, f64) {
+        match side {
+            PositionType::Long => (self.q_yes + size, self.q_no),
+            PositionType::Short => (self.q_yes, self.q_no + size),
+        }
+    }
+}
+
+impl PricingMechanism for Lmsr {
+    fn name(&self) -> &'static str {
+        "lmsr"
+    }
+
+    fn price(&self) -> f64 {
+        1.0 / (1.0 + ((self.q_no - self.q_yes) / self.liquidity).exp())
+    }
+
+    fn quote(&self, side: PositionType, size: f64) -> Result<f64> {
+        let (q_yes, q_no) = self.shifted(side, size);
+        check_finite(self.cost(q_yes, q_no) - self.cost(self.q_yes, self.q_no))
+    }
+
+    fn execute(&mut self, side: PositionType, size: f64) -> Result<Fill> {
+        let price_before = self.price();
+        let cost = self.quote(side, size)?;
+        (self.q_yes, self.q_no) = self.shifted(side, size);
+        Ok(Fill::new(side, size, cost, price_before, self.price()))
+    }
+
+    fn worst_case_loss(&self) -> Option<f64> {
+        let least_likely = self.initial_price.min(1.0 - self.initial_price);
+        Some(self.liquidity * (1.0 / least_likely).ln())
+    }
+
+    fn clone_box(&self) -> Box<dyn PricingMechanism> {
+        Box::new(self.clone())
+    }
+}
+
+/// Constant-product pool of YES and NO contracts
+#[derive(Debug, Clone)]
+pub struct ConstantProduct {
+    yes_reserve: f64,
+    no_reserve: f64,
+    initial_funding: f64,
+}
+
+impl ConstantProduct {
+    /// Create a pool with invariant `liquidity^2` starting at `initial_price`
+    pub fn new(liquidity: f64, initial_price: f64) -> Self {
+        let p = initial_price.clamp(MIN_INITIAL_PRICE, MAX_INITIAL_PRICE);
+        let yes_reserve = liquidity * ((1.0 - p) / p).sqrt();
+        let no_reserve = liquidity * (p / (1.0 - p)).sqrt();
+        ConstantProduct {
+            yes_reserve,
+            no_reserve,
+            initial_funding: yes_reserve.max(no_reserve),
+        }
+    }
+
+    /// Reserves ordered as (traded side, other side)
+    fn reserves(&self, side: PositionType) -> (f64, f64) {
+        match side {
+            PositionType::Long => (self.yes_reserve, self.no_reserve),
+            PositionType::Short => (self.no_reserve, self.yes_reserve),
+        }
+    }
+
+    /// Collateral cost and new reserves for trading `size` contracts of `side`
+    ///
+    /// Buying deposits `c` collateral as `c` complete sets and withdraws
+    /// `size` contracts; selling does the reverse, keeping the product fixed.
+    fn trade(&self, side: PositionType, size: f64) -> Result<(f64, f64, f64)> {
+        let (x, y) = self.reserves(side);
+        let cost = if size >= 0.0 {
+            let b = x + y - size;
+            (-b + (b * b + 4.0 * size * y).sqrt()) / 2.0
+        } else {
+            let s = -size;
+            let b = x + s + y;
+            -((b - (b * b - 4.0 * s * y).sqrt()) / 2.0)
+        };
+        let cost = check_finite(cost)?;
+        let new_x = x + cost - size;
+        let new_y = y + cost;
+        if new_x <= 0.0 || new_y <= 0.0 {
+            return Err(SimulatorError::InvalidMarketState(
+                "trade would drain the constant-product pool".to_string(),
+            ));
+        }
+        Ok((cost, new_x, new_y))
+    }
+}
+
+impl PricingMechanism for ConstantProduct {
+    fn name(&self) -> &'static str {
+        "constant_product"
+    }
+
+    fn price(&self) -> f64 {
+        self.no_reserve / (self.yes_reserve + self.no_reserve)
+    }
+
+    fn quote(&self, side: PositionType, size: f64) -> Result<f64> {
+        self.trade(side, size).map(|(cost, _, _)| cost)
+    }
+
+    fn execute(&mut self, side: PositionType, size: f64) -> Result<Fill> {
+        let price_before = self.price();
+        let (cost, traded, other) = self.trade(side, size)?;
+        match side {
+            PositionType::Long => (self.yes_reserve, self.no_reserve) = (traded, other),
+            PositionType::Short => (self.no_reserve, self.yes_reserve) = (traded, other),
+        }
+        Ok(Fill::new(side, size, cost, price_before, self.price()))
+    }
+
+    fn worst_case_loss(&self) -> Option<f64> {
+        Some(self.initial_funding)
+    }
+
+    fn clone_box(&self) -> Box<dyn PricingMechanism> {
+        Box::new(self.clone())
+    }
+}
+
+/// Serializable selection of a built-in pricing mechanism
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum PricingConfig {
+    /// Fill at the oracle BSI with no price impact
+    #[default]
+    Oracle,
+    /// LMSR market maker with liquidity parameter `b`
+    Lmsr { liquidity: f64 },
+    /// Constant-product YES/NO pool with invariant `liquidity^2`
+    ConstantProduct { liquidity: f64 },
+}
+
+impl PricingConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            PricingConfig::Lmsr { liquidity } | PricingConfig::ConstantProduct { liquidity }
+                if !(liquidity.is_finite() && liquidity > 0.0) =>
+            {
+                Err(SimulatorError::InvalidConfig(
+                    "AMM liquidity must be greater than 0".to_string(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Build the mechanism starting at the initial BSI
+    pub fn build(&self, initial_bsi: BSI) -> Box<dyn PricingMechanism> {
+        match *self {
+            PricingConfig::Oracle => Box::new(OraclePricing::new(initial_bsi)),
+            PricingConfig::Lmsr { liquidity } => {
+                Box::new(Lmsr::new(liquidity, initial_bsi.value()))
+            }
+            PricingConfig::ConstantProduct { liquidity } => {
+                Box::new(ConstantProduct::new(liquidity, initial_bsi.value()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lmsr_price_impact() {
+        let mut lmsr = Lmsr::new(100.0, 0.5);
+        assert!((lmsr.price() - 0.5).abs() < 1e-9);
+
+        let fill = lmsr.execute(PositionType::Long, 50.0).unwrap();
+        assert!(fill.price_after > fill.price_before);
+        assert!(fill.average_price > 0.5 && fill.average_price < fill.price_after);
+        assert!(fill.slippage > 0.0);
+
+        // Selling back returns to the starting price and cost
+        let back = lmsr.execute(PositionType::Long, -50.0).unwrap();
+        assert!((lmsr.price() - 0.5).abs() < 1e-9);
+        assert!((fill.cost + back.cost).abs() < 1e-9);
+        assert!((lmsr.worst_case_loss().unwrap() - 100.0 * 2f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_constant_product_complete_set_equivalence() {
+        let mut pool = ConstantProduct::new(100.0, 0.3);
+        assert!((pool.price() - 0.3).abs() < 1e-9);
+
+        let long = pool.clone().execute(PositionType::Long, 20.0).unwrap();
+        assert!(long.price_after > 0.3);
+
+        // Buying NO costs one unit per contract minus the proceeds of selling YES
+        let buy_no = pool.quote(PositionType::Short, 20.0).unwrap();
+        let sell_yes = pool.quote(PositionType::Long, -20.0).unwrap();
+        assert!((buy_no - (20.0 + sell_yes)).abs() < 1e-9);
+
+        let fill = pool.execute(PositionType::Short, 20.0).unwrap();
+        assert!(fill.price_after < 0.3);
+        assert!(fill.slippage > 0.0);
+    }
+
+    #[test]
+    fn test_oracle_pricing_follows_bsi() {
+        let mut pricing = OraclePricing::new(BSI::new(0.4).unwrap());
+        pricing.on_oracle_update(BSI::new(0.6).unwrap());
+
+        let fill = pricing.execute(PositionType::Short, 10.0).unwrap();
+        assert!((fill.cost - 4.0).abs() < 1e-9);
+        assert_eq!(fill.slippage, 0.0);
+    }
+}
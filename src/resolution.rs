@@ -0,0 +1,526 @@
+//! Pluggable market resolution rules
+
+use crate::error::{Result, SimulatorError};
+use crate::types::BSI;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// Outcome of feeding an oracle update to a resolution rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleStatus {
+    /// Resolution condition does not hold
+    NotMet,
+    /// Condition holds but has not been confirmed yet
+    Pending,
+    /// Market should resolve
+    Met,
+}
+
+/// Decides when a market resolves from the stream of oracle updates
+///
+/// Rules are stateful: the market feeds every oracle update through
+/// [`ResolutionRule::observe`] in time order.
+pub trait ResolutionRule: Debug + Send + Sync {
+    /// Short rule name used in reports
+    fn name(&self) -> &'static str;
+
+    /// Observe an oracle update and report the resolution status
+    fn observe(&mut self, bsi: BSI, timestamp: DateTime<Utc>) -> RuleStatus;
+
+    /// Clear any accumulated state
+    fn reset(&mut self);
+
+    /// Clone into a boxed trait object
+    fn clone_box(&self) -> Box<dyn ResolutionRule>;
+}
+
+impl Clone for Box<dyn ResolutionRule> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Replay a BSI path through a fresh copy of `rule` and return the first resolution time
+pub fn first_resolution(
+    rule: &dyn ResolutionRule,
+    path: &[(DateTime<Utc>, BSI)],
+) -> Option<DateTime<Utc>> {
+    let mut rule = rule.clone_box();
+    rule.reset();
+    path.iter()
+        .find(|(timestamp, bsi)| rule.observe(*bsi, *timestamp) == RuleStatus::Met)
+        .map(|(timestamp, _)| *timestamp)
+}
+
+/// How the market's resolution compares with the latent belief it tracks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolutionAccuracy {
+    /// When the resolution rule was first met by the latent belief
+    pub latent_resolution_time: Option<DateTime<Utc>>,
+    /// Market resolved although the latent belief had not met the rule
+    pub false_positive: bool,
+    /// Latent belief met the rule but the market never resolved
+    pub false_negative: bool,
+    /// Seconds from the latent resolution to the market resolution
+    pub detection_delay_secs: Option<i64>,
+}
+
+impl ResolutionAccuracy {
+    /// Compare the latent and observed resolution times of a run
+    pub fn new(latent: Option<DateTime<Utc>>, observed: Option<DateTime<Utc>>) -> Self {
+        let (false_positive, false_negative, detection_delay_secs) = match (latent, observed) {
+            (Some(latent), Some(observed)) if latent <= observed => {
+                (false, false, Some((observed - latent).num_seconds()))
+            }
+            (_, Some(_)) => (true, false, None),
+            (Some(_), None) => (false, true, None),
+            (None, None) => (false, false, None),
+        };
+
+        ResolutionAccuracy {
+            latent_resolution_time: latent,
+            false_positive,
+            false_negative,
+            detection_delay_secs,
+        }
+    }
+}
+
+/// Side of the threshold that resolves the market
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdDirection {
+    /// Resolve when BSI is at or above threshold
+    Above,
+    /// Resolve when BSI is at or below threshold
+    Below,
+}
+
+impl ThresholdDirection {
+    /// Check whether a value satisfies the threshold in this direction
+    pub fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            ThresholdDirection::Above => value >= threshold,
+            ThresholdDirection::Below => value <= threshold,
+        }
+    }
+}
+
+/// Resolve on the first update that reaches the threshold
+#[derive(Debug, Clone)]
+pub struct FirstCrossing {
+    threshold: f64,
+    direction: ThresholdDirection,
+}
+
+impl FirstCrossing {
+    /// Create a first-crossing rule
+    pub fn new(threshold: f64, direction: ThresholdDirection) -> Self {
+        FirstCrossing {
+            threshold,
+            direction,
+        }
+    }
+}
+
+impl ResolutionRule for FirstCrossing {
+    fn name(&self) -> &'static str {
+        match self.direction {
+            ThresholdDirection::Above => "first_crossing",
+            ThresholdDirection::Below => "below_threshold",
+        }
+    }
+
+    fn observe(&mut self, bsi: BSI, _timestamp: DateTime<Utc>) -> RuleStatus {
+        if self.direction.holds(bsi.value(), self.threshold) {
+            RuleStatus::Met
+        } else {
+            RuleStatus::NotMet
+        }
+    }
+
+    fn reset(&mut self) {}
+
+    fn clone_box(&self) -> Box<dyn ResolutionRule> {
+        Box::new(self.clone())
+    }
+}
+
+/// Resolve once the threshold has held continuously for a window
+#[derive(Debug, Clone)]
+pub struct PersistenceWindow {
+    threshold: f64,
+    direction: ThresholdDirection,
+    window: Duration,
+    held_since: Option<DateTime<Utc>>,
+}
+
+impl PersistenceWindow {
+    /// Create a persistence-window rule
+    pub fn new(threshold: f64, direction: ThresholdDirection, window: Duration) -> Self {
+        PersistenceWindow {
+            threshold,
+            direction,
+            window,
+            held_since: None,
+        }
+    }
+}
+
+impl ResolutionRule for PersistenceWindow {
+    fn name(&self) -> &'static str {
+        "persistence_window"
+    }
+
+    fn observe(&mut self, bsi: BSI, timestamp: DateTime<Utc>) -> RuleStatus {
+        if !self.direction.holds(bsi.value(), self.threshold) {
+            // Dropping back resets the window
+            self.held_since = None;
+            return RuleStatus::NotMet;
+        }
+
+        let since = *self.held_since.get_or_insert(timestamp);
+        if timestamp - since >= self.window {
+            RuleStatus::Met
+        } else {
+            RuleStatus::Pending
+        }
+    }
+
+    fn reset(&mut self) {
+        self.held_since = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn ResolutionRule> {
+        Box::new(self.clone())
+    }
+}
+
+/// Resolve when the time-weighted average BSI over a trailing window reaches the threshold
+#[derive(Debug, Clone)]
+pub struct TimeWeightedAverage {
+    threshold: f64,
+    direction: ThresholdDirection,
+    window: Duration,
+    samples: VecDeque<(DateTime<Utc>, f64)>,
+    first_seen: Option<DateTime<Utc>>,
+}
+
+impl TimeWeightedAverage {
+    /// Create a time-weighted-average rule
+    pub fn new(threshold: f64, direction: ThresholdDirection, window: Duration) -> Self {
+        TimeWeightedAverage {
+            threshold,
+            direction,
+            window,
+            samples: VecDeque::new(),
+            first_seen: None,
+        }
+    }
+
+    /// Average over `[window_start, now]` where each value is weighted by the
+    /// time it was in effect, from its update (or the window start) until the
+    /// next update (or `now`)
+    fn average(&self, window_start: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+        let mut weighted = 0.0;
+        let mut total = 0.0;
+
+        for (i, &(timestamp, value)) in self.samples.iter().enumerate() {
+            let until = self.samples.get(i + 1).map_or(now, |&(next, _)| next);
+            let from = timestamp.max(window_start);
+            let weight = (until - from).num_milliseconds().max(0) as f64;
+            weighted += value * weight;
+            total += weight;
+        }
+
+        if total > 0.0 {
+            weighted / total
+        } else {
+            self.samples.back().map_or(0.0, |&(_, value)| value)
+        }
+    }
+}
+
+impl ResolutionRule for TimeWeightedAverage {
+    fn name(&self) -> &'static str {
+        "time_weighted_average"
+    }
+
+    fn observe(&mut self, bsi: BSI, timestamp: DateTime<Utc>) -> RuleStatus {
+        let first_seen = *self.first_seen.get_or_insert(timestamp);
+        let window_start = timestamp - self.window;
+
+        self.samples.push_back((timestamp, bsi.value()));
+        // Keep the sample in effect at the window start to weight the leading edge
+        while self.samples.len() > 2 && self.samples[1].0 <= window_start {
+            self.samples.pop_front();
+        }
+
+        let covered = timestamp - first_seen >= self.window;
+        if covered
+            && self
+                .direction
+                .holds(self.average(window_start, timestamp), self.threshold)
+        {
+            RuleStatus::Met
+        } else if self.direction.holds(bsi.value(), self.threshold) {
+            RuleStatus::Pending
+        } else {
+            RuleStatus::NotMet
+        }
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+        self.first_seen = None;
+    }
+
+    fn clone_box(&self) -> Box<dyn ResolutionRule> {
+        Box::new(self.clone())
+    }
+}
+
+/// Resolve when the median of the last N oracle updates reaches the threshold
+#[derive(Debug, Clone)]
+pub struct MedianOfLast {
+    threshold: f64,
+    direction: ThresholdDirection,
+    count: usize,
+    recent: VecDeque<f64>,
+}
+
+impl MedianOfLast {
+    /// Create a median-of-last-N rule
+    pub fn new(threshold: f64, direction: ThresholdDirection, count: usize) -> Self {
+        MedianOfLast {
+            threshold,
+            direction,
+            count,
+            recent: VecDeque::with_capacity(count),
+        }
+    }
+
+    fn median(&self) -> f64 {
+        let mut values: Vec<f64> = self.recent.iter().copied().collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        let mid = values.len() / 2;
+        if values.len() % 2 == 1 {
+            values[mid]
+        } else {
+            (values[mid - 1] + values[mid]) / 2.0
+        }
+    }
+}
+
+impl ResolutionRule for MedianOfLast {
+    fn name(&self) -> &'static str {
+        "median_of_last"
+    }
+
+    fn observe(&mut self, bsi: BSI, _timestamp: DateTime<Utc>) -> RuleStatus {
+        if self.recent.len() == self.count {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(bsi.value());
+
+        if self.recent.len() == self.count && self.direction.holds(self.median(), self.threshold) {
+            RuleStatus::Met
+        } else if self.direction.holds(bsi.value(), self.threshold) {
+            RuleStatus::Pending
+        } else {
+            RuleStatus::NotMet
+        }
+    }
+
+    fn reset(&mut self) {
+        self.recent.clear();
+    }
+
+    fn clone_box(&self) -> Box<dyn ResolutionRule> {
+        Box::new(self.clone())
+    }
+}
+
+/// Two-sided range market: resolve when BSI leaves the band on either side
+#[derive(Debug, Clone)]
+pub struct TwoSidedRange {
+    lower: f64,
+    upper: f64,
+}
+
+impl TwoSidedRange {
+    /// Create a range rule resolving outside `[lower, upper]`
+    pub fn new(lower: f64, upper: f64) -> Self {
+        TwoSidedRange { lower, upper }
+    }
+}
+
+impl ResolutionRule for TwoSidedRange {
+    fn name(&self) -> &'static str {
+        "two_sided_range"
+    }
+
+    fn observe(&mut self, bsi: BSI, _timestamp: DateTime<Utc>) -> RuleStatus {
+        if bsi.value() <= self.lower || bsi.value() >= self.upper {
+            RuleStatus::Met
+        } else {
+            RuleStatus::NotMet
+        }
+    }
+
+    fn reset(&mut self) {}
+
+    fn clone_box(&self) -> Box<dyn ResolutionRule> {
+        Box::new(self.clone())
+    }
+}
+
+/// Serializable selection of a built-in resolution rule
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ResolutionRuleConfig {
+    /// Resolve on the first update at or above threshold
+    FirstCrossing,
+    /// Resolve after BSI holds at or above threshold for `persistence_hours`
+    #[default]
+    Persistence,
+    /// Resolve when the time-weighted average BSI over the window reaches threshold
+    TimeWeightedAverage { window_hours: u32 },
+    /// Resolve when the median of the last N updates reaches threshold
+    MedianOfLast { updates: usize },
+    /// Resolve on the first update at or below threshold
+    BelowThreshold,
+    /// Resolve when BSI leaves `[lower, upper]` on either side
+    Range { lower: f64, upper: f64 },
+}
+
+impl ResolutionRuleConfig {
+    /// Validate rule parameters
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            ResolutionRuleConfig::TimeWeightedAverage { window_hours: 0 } => Err(
+                SimulatorError::InvalidConfig("TWAP window must be greater than 0".to_string()),
+            ),
+            ResolutionRuleConfig::MedianOfLast { updates: 0 } => Err(
+                SimulatorError::InvalidConfig("Median rule needs at least 1 update".to_string()),
+            ),
+            ResolutionRuleConfig::Range { lower, upper }
+                if !(0.0..=1.0).contains(&lower)
+                    || !(0.0..=1.0).contains(&upper)
+                    || lower >= upper =>
+            {
+                Err(SimulatorError::InvalidConfig(
+                    "Range bounds must satisfy 0.0 <= lower < upper <= 1.0".to_string(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Build the rule for a market with the given threshold and persistence window
+    pub fn build(&self, threshold: f64, persistence_hours: u32) -> Box<dyn ResolutionRule> {
+        let above = ThresholdDirection::Above;
+        match *self {
+            ResolutionRuleConfig::FirstCrossing => Box::new(FirstCrossing::new(threshold, above)),
+            ResolutionRuleConfig::Persistence => Box::new(PersistenceWindow::new(
+                threshold,
+                above,
+                Duration::hours(persistence_hours as i64),
+            )),
+            ResolutionRuleConfig::TimeWeightedAverage { window_hours } => Box::new(
+                TimeWeightedAverage::new(threshold, above, Duration::hours(window_hours as i64)),
+            ),
+            ResolutionRuleConfig::MedianOfLast { updates } => {
+                Box::new(MedianOfLast::new(threshold, above, updates))
+            }
+            ResolutionRuleConfig::BelowThreshold => {
+                Box::new(FirstCrossing::new(threshold, ThresholdDirection::Below))
+            }
+            ResolutionRuleConfig::Range { lower, upper } => {
+                Box::new(TwoSidedRange::new(lower, upper))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+
+    fn path(values: &[f64]) -> Vec<(DateTime<Utc>, BSI)> {
+        let start = default_start_time();
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (start + Duration::hours(i as i64), BSI::new(v).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rules_on_same_path() {
+        let path = path(&[0.5, 0.8, 0.6, 0.8, 0.8, 0.8, 0.8]);
+        let start = default_start_time();
+
+        let first = FirstCrossing::new(0.75, ThresholdDirection::Above);
+        assert_eq!(
+            first_resolution(&first, &path),
+            Some(start + Duration::hours(1))
+        );
+
+        let persistent =
+            PersistenceWindow::new(0.75, ThresholdDirection::Above, Duration::hours(2));
+        assert_eq!(
+            first_resolution(&persistent, &path),
+            Some(start + Duration::hours(5))
+        );
+
+        let median = MedianOfLast::new(0.75, ThresholdDirection::Above, 3);
+        assert_eq!(
+            first_resolution(&median, &path),
+            Some(start + Duration::hours(3))
+        );
+
+        let below = FirstCrossing::new(0.55, ThresholdDirection::Below);
+        assert_eq!(first_resolution(&below, &path), Some(start));
+    }
+
+    #[test]
+    fn test_time_weighted_average_and_range() {
+        let path = path(&[0.5, 0.9, 0.9, 0.9, 0.9]);
+        let start = default_start_time();
+
+        let mut twap =
+            TimeWeightedAverage::new(0.75, ThresholdDirection::Above, Duration::hours(2));
+        // 0.5 held for the first hour of the window and 0.9 for the second
+        for &(timestamp, bsi) in &path[..3] {
+            twap.observe(bsi, timestamp);
+        }
+        let average = twap.average(start, start + Duration::hours(2));
+        assert!((average - 0.7).abs() < 1e-12);
+        assert_eq!(
+            first_resolution(&twap, &path),
+            Some(start + Duration::hours(3))
+        );
+
+        let range = TwoSidedRange::new(0.3, 0.85);
+        assert_eq!(
+            first_resolution(&range, &path),
+            Some(start + Duration::hours(1))
+        );
+    }
+
+    #[test]
+    fn test_rule_config_validation() {
+        assert!(ResolutionRuleConfig::Range {
+            lower: 0.6,
+            upper: 0.4
+        }
+        .validate()
+        .is_err());
+        assert!(ResolutionRuleConfig::MedianOfLast { updates: 0 }
+            .validate()
+            .is_err());
+        assert!(ResolutionRuleConfig::Persistence.validate().is_ok());
+    }
+}
@@ -1,6 +1,25 @@
-//! Predefined simulation scenarios
+//! Predefined simulation scenarios and declarative scenario timelines
+//!
+//! A [`ScenarioDefinition`] is a timeline of events, each an action taken
+//! when its trigger fires. The predefined scenarios are expressed in the same
+//! format, and custom definitions can be loaded from and saved to JSON or TOML.
 
+use crate::error::{Result, SimulatorError};
+use crate::participant::ParticipantBehavior;
+use crate::rng::SimRng;
+use crate::types::BSI;
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Longest trigger delay or trading pause, in hours (about 114 years)
+const MAX_EVENT_HOURS: f64 = 1_000_000.0;
+
+/// Convert a validated number of hours to a duration
+pub(crate) fn hours_to_duration(hours: f64) -> Duration {
+    Duration::milliseconds((hours.clamp(0.0, MAX_EVENT_HOURS) * 3_600_000.0) as i64)
+}
 
 /// Predefined market scenarios for simulation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -23,7 +42,7 @@ pub enum Scenario {
     FlashCrash,
     /// Parabolic rise
     ParabolicRise,
-    /// Custom scenario (user-defined)
+    /// Custom scenario, run from a user-defined [`ScenarioDefinition`]
     Custom,
 }
 
@@ -40,7 +59,7 @@ impl Scenario {
             Scenario::LowActivity => "Minimal trading with slow BSI drift",
             Scenario::FlashCrash => "Sudden sharp drop followed by recovery",
             Scenario::ParabolicRise => "Accelerating upward movement",
-            Scenario::Custom => "User-defined scenario parameters",
+            Scenario::Custom => "User-defined scenario timeline",
         }
     }
 
@@ -74,6 +93,50 @@ impl Scenario {
         }
     }
 
+    /// Timeline of events that makes up the scenario
+    ///
+    /// `Custom` has an empty timeline; run a [`ScenarioDefinition`] instead.
+    /// The flash crash and sentiment reversal shocks fire once, at hour 168
+    /// and hour 240; earlier releases repeated them on every update of day 7
+    /// and day 10, which pinned the belief at the bound for a whole day.
+    pub fn definition(&self) -> ScenarioDefinition {
+        let target =
+            |target: f64| ScenarioEvent::new(Trigger::Start, ScenarioAction::SetTarget { target });
+        let events = match self {
+            Scenario::BullishTrend => vec![target(0.8)],
+            Scenario::BearishTrend => vec![target(0.2)],
+            Scenario::SentimentReversal => vec![
+                target(0.9),
+                ScenarioEvent::new(
+                    Trigger::After { hours: 240.0 },
+                    ScenarioAction::Shock { magnitude: 0.4 },
+                ),
+            ],
+            Scenario::ConsensusFormation => {
+                vec![ScenarioEvent::new(
+                    Trigger::Start,
+                    ScenarioAction::TargetThreshold,
+                )]
+            }
+            Scenario::HighVolatility => vec![ScenarioEvent::new(
+                Trigger::Random { probability: 0.1 },
+                ScenarioAction::RandomShock { max_magnitude: 0.2 },
+            )],
+            Scenario::FlashCrash => vec![ScenarioEvent::new(
+                Trigger::After { hours: 168.0 },
+                ScenarioAction::Shock { magnitude: -0.3 },
+            )],
+            Scenario::ParabolicRise => vec![target(0.95)],
+            Scenario::Sideways | Scenario::LowActivity | Scenario::Custom => Vec::new(),
+        };
+
+        ScenarioDefinition {
+            name: format!("{:?}", self),
+            description: self.description().to_string(),
+            events,
+        }
+    }
+
     /// Get all available scenarios
     pub fn all() -> Vec<Scenario> {
         vec![
@@ -90,6 +153,254 @@ impl Scenario {
     }
 }
 
+/// Condition that fires a timeline event
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Trigger {
+    /// Before the first update
+    Start,
+    /// Once `hours` have elapsed since the start
+    After { hours: f64 },
+    /// Once the simulation clock reaches `time`
+    At { time: DateTime<Utc> },
+    /// Once the reported BSI crosses from below `level` to at or above it
+    BsiAbove { level: f64 },
+    /// Once the reported BSI crosses from above `level` to at or below it
+    BsiBelow { level: f64 },
+    /// On every update with `probability`; fires repeatedly
+    Random { probability: f64 },
+}
+
+/// Change made to the running simulation when an event fires
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ScenarioAction {
+    /// Drift the belief toward `target`
+    SetTarget { target: f64 },
+    /// Drift the belief toward the market threshold
+    TargetThreshold,
+    /// Stop drifting toward a target
+    ClearTarget,
+    /// Move the belief by `magnitude`
+    Shock { magnitude: f64 },
+    /// Move the belief by a uniform draw in `[-max_magnitude, max_magnitude)`
+    RandomShock { max_magnitude: f64 },
+    /// Scale the belief process volatility relative to the configured oracle
+    VolatilityRegime { multiplier: f64 },
+    /// Halt trading for `hours` and cancel resting orders
+    PauseTrading { hours: f64 },
+    /// Add `count` participants, cycling through all behaviors if none is given
+    ParticipantInflux {
+        count: usize,
+        behavior: Option<ParticipantBehavior>,
+    },
+}
+
+/// An action taken when its trigger fires
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioEvent {
+    /// When the event fires
+    pub trigger: Trigger,
+    /// What the event does
+    pub action: ScenarioAction,
+}
+
+impl ScenarioEvent {
+    /// Create an event
+    pub fn new(trigger: Trigger, action: ScenarioAction) -> Self {
+        ScenarioEvent { trigger, action }
+    }
+}
+
+/// A scenario as a timeline of events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioDefinition {
+    /// Scenario name
+    pub name: String,
+    /// Scenario description
+    #[serde(default)]
+    pub description: String,
+    /// Events in the order they are checked on each update
+    #[serde(default)]
+    pub events: Vec<ScenarioEvent>,
+}
+
+impl ScenarioDefinition {
+    /// Create an empty timeline
+    pub fn new(name: impl Into<String>) -> Self {
+        ScenarioDefinition {
+            name: name.into(),
+            description: String::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Add an event to the timeline
+    pub fn event(mut self, trigger: Trigger, action: ScenarioAction) -> Self {
+        self.events.push(ScenarioEvent::new(trigger, action));
+        self
+    }
+
+    /// Validate the timeline
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: &str| Err(SimulatorError::InvalidConfig(message.to_string()));
+        let unit = |value: f64| (0.0..=1.0).contains(&value);
+        let hours = |value: f64| (0.0..=MAX_EVENT_HOURS).contains(&value);
+
+        for event in &self.events {
+            let trigger_ok = match event.trigger {
+                Trigger::Start | Trigger::At { .. } => true,
+                Trigger::After { hours: h } => hours(h),
+                Trigger::BsiAbove { level } | Trigger::BsiBelow { level } => unit(level),
+                Trigger::Random { probability } => unit(probability),
+            };
+            if !trigger_ok {
+                return invalid(
+                    "Trigger hours must be in [0, 1e6] and levels and \
+                     probabilities in [0, 1]",
+                );
+            }
+
+            let action_ok = match event.action {
+                ScenarioAction::SetTarget { target } => unit(target),
+                ScenarioAction::TargetThreshold | ScenarioAction::ClearTarget => true,
+                ScenarioAction::Shock { magnitude } => (-1.0..=1.0).contains(&magnitude),
+                ScenarioAction::RandomShock { max_magnitude } => {
+                    max_magnitude > 0.0 && max_magnitude <= 1.0
+                }
+                ScenarioAction::VolatilityRegime { multiplier } => {
+                    multiplier.is_finite() && multiplier >= 0.0
+                }
+                ScenarioAction::PauseTrading { hours: h } => hours(h),
+                ScenarioAction::ParticipantInflux { .. } => true,
+            };
+            if !action_ok {
+                return invalid(
+                    "Targets must be in [0, 1], shocks in [-1, 1], multipliers >= 0 \
+                     and pauses in [0, 1e6] hours",
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a timeline from JSON
+    pub fn from_json(input: &str) -> Result<Self> {
+        let definition: ScenarioDefinition = serde_json::from_str(input)?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Serialize the timeline to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a timeline from TOML
+    pub fn from_toml(input: &str) -> Result<Self> {
+        let definition: ScenarioDefinition = toml::from_str(input)
+            .map_err(|e| SimulatorError::DataError(format!("invalid TOML timeline: {}", e)))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Serialize the timeline to TOML
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self)
+            .map_err(|e| SimulatorError::DataError(format!("cannot write TOML timeline: {}", e)))
+    }
+
+    /// Load a `.toml` file, or a JSON file with any other extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path)?;
+        if is_toml(path) {
+            Self::from_toml(&input)
+        } else {
+            Self::from_json(&input)
+        }
+    }
+
+    /// Save to a `.toml` file, or as JSON with any other extension
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let output = if is_toml(path) {
+            self.to_toml()?
+        } else {
+            self.to_json()?
+        };
+        std::fs::write(path, output)?;
+        Ok(())
+    }
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+/// Tracks which events of a timeline have fired during a run
+#[derive(Debug, Clone)]
+pub struct ScenarioTimeline {
+    events: Vec<ScenarioEvent>,
+    fired: Vec<bool>,
+    previous_bsi: Option<f64>,
+}
+
+impl ScenarioTimeline {
+    /// Start a run of `definition`
+    pub fn new(definition: &ScenarioDefinition) -> Self {
+        ScenarioTimeline {
+            events: definition.events.clone(),
+            fired: vec![false; definition.events.len()],
+            previous_bsi: None,
+        }
+    }
+
+    /// Actions of the events firing at `now`, in timeline order
+    ///
+    /// Random shocks are drawn here, so they are returned as plain shocks.
+    /// BSI triggers fire on a crossing between the previous update and this
+    /// one, so a level already passed at the first update does not fire.
+    pub fn due(
+        &mut self,
+        now: DateTime<Utc>,
+        start: DateTime<Utc>,
+        bsi: BSI,
+        rng: &mut SimRng,
+    ) -> Vec<ScenarioAction> {
+        let previous = self.previous_bsi.replace(bsi.value());
+        let mut actions = Vec::new();
+        for (event, fired) in self.events.iter().zip(self.fired.iter_mut()) {
+            let fires = match event.trigger {
+                Trigger::Random { probability } => rng.gen_bool(probability),
+                _ if *fired => false,
+                Trigger::Start => true,
+                Trigger::After { hours } => now - start >= hours_to_duration(hours),
+                Trigger::At { time } => now >= time,
+                Trigger::BsiAbove { level } => {
+                    previous.is_some_and(|p| p < level) && bsi.value() >= level
+                }
+                Trigger::BsiBelow { level } => {
+                    previous.is_some_and(|p| p > level) && bsi.value() <= level
+                }
+            };
+            if !fires {
+                continue;
+            }
+
+            *fired = true;
+            actions.push(match event.action {
+                ScenarioAction::RandomShock { max_magnitude } => ScenarioAction::Shock {
+                    magnitude: rng.gen_range(-max_magnitude..max_magnitude),
+                },
+                action => action,
+            });
+        }
+
+        actions
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,6 +412,108 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_definitions_round_trip_through_json_and_toml() {
+        for scenario in Scenario::all() {
+            let definition = scenario.definition();
+            assert!(definition.validate().is_ok());
+            let json = definition.to_json().unwrap();
+            assert_eq!(ScenarioDefinition::from_json(&json).unwrap(), definition);
+            let toml = definition.to_toml().unwrap();
+            assert_eq!(ScenarioDefinition::from_toml(&toml).unwrap(), definition);
+        }
+
+        let toml = r#"
+            name = "Bad target"
+
+            [[events]]
+            trigger = "Start"
+            action = { SetTarget = { target = 1.5 } }
+        "#;
+        assert!(ScenarioDefinition::from_toml(toml).is_err());
+
+        let json = r#"{
+            "name": "Bad target",
+            "events": [{"trigger": "Start", "action": {"SetTarget": {"target": 1.5}}}]
+        }"#;
+        assert!(ScenarioDefinition::from_json(json).is_err());
+
+        // Delays and pauses must fit in a run
+        let pause = |hours| {
+            ScenarioDefinition::new("Long pause")
+                .event(Trigger::Start, ScenarioAction::PauseTrading { hours })
+                .validate()
+        };
+        assert!(pause(MAX_EVENT_HOURS).is_ok());
+        assert!(pause(1e12).is_err());
+        let after = ScenarioDefinition::new("Late shock").event(
+            Trigger::After { hours: 1e12 },
+            ScenarioAction::Shock { magnitude: 0.1 },
+        );
+        assert!(after.validate().is_err());
+    }
+
+    #[test]
+    fn test_timeline_fires_each_event_once() {
+        use crate::clock::default_start_time;
+        use crate::rng::{RngStreams, SCENARIO_STREAM};
+
+        let definition = ScenarioDefinition::new("test")
+            .event(
+                Trigger::BsiAbove { level: 0.6 },
+                ScenarioAction::ClearTarget,
+            )
+            .event(
+                Trigger::After { hours: 1.5 },
+                ScenarioAction::Shock { magnitude: 0.1 },
+            )
+            .event(
+                Trigger::BsiBelow { level: 0.55 },
+                ScenarioAction::PauseTrading { hours: 1.0 },
+            );
+        let mut timeline = ScenarioTimeline::new(&definition);
+        let mut rng = RngStreams::new(1).stream(SCENARIO_STREAM);
+        let start = default_start_time();
+        let mut due = |minutes: i64, bsi: f64| {
+            let now = start + Duration::minutes(minutes);
+            timeline.due(now, start, BSI::new(bsi).unwrap(), &mut rng)
+        };
+
+        // Starting below 0.55 is not a crossing
+        assert!(due(0, 0.5).is_empty());
+        assert_eq!(due(60, 0.65), vec![ScenarioAction::ClearTarget]);
+        assert!(due(85, 0.7).is_empty());
+        assert_eq!(due(90, 0.7), vec![ScenarioAction::Shock { magnitude: 0.1 }]);
+        assert_eq!(due(95, 0.5), vec![ScenarioAction::PauseTrading { hours: 1.0 }]);
+        assert!(due(100, 0.7).is_empty());
+        assert!(due(120, 0.5).is_empty());
+    }
+
+    #[test]
+    fn test_builtin_shocks_fire_once() {
+        use crate::clock::default_start_time;
+        use crate::rng::{RngStreams, SCENARIO_STREAM};
+
+        let start = default_start_time();
+        let mut rng = RngStreams::new(1).stream(SCENARIO_STREAM);
+        for (scenario, hours, magnitude) in [
+            (Scenario::FlashCrash, 168, -0.3),
+            (Scenario::SentimentReversal, 240, 0.4),
+        ] {
+            let mut timeline = ScenarioTimeline::new(&scenario.definition());
+            let shocks: Vec<i64> = (0..=24 * 12 * 12)
+                .filter(|tick| {
+                    let now = start + Duration::minutes(5 * tick);
+                    timeline
+                        .due(now, start, BSI::default(), &mut rng)
+                        .contains(&ScenarioAction::Shock { magnitude })
+                })
+                .collect();
+            // A single shock at the start of the day, not one per update
+            assert_eq!(shocks, vec![hours * 12]);
+        }
+    }
+
     #[test]
     fn test_scenario_parameters() {
         let scenario = Scenario::BullishTrend;
@@ -0,0 +1,542 @@
+//! Global sensitivity analysis of market outcomes
+//!
+//! Parameters are addressed by config path as in [`Sweep`] and varied over a
+//! range mapped onto the unit interval. Morris screening ranks parameters by
+//! their elementary effects along randomized one-at-a-time trajectories;
+//! Sobol indices split the variance of an output into the share explained by
+//! each parameter alone (first order) and together with its interactions
+//! (total order). Every design point runs the same replicate seeds, and
+//! confidence intervals come from bootstrap resampling of the design.
+
+use crate::analytics::{quantile, ConfidenceInterval};
+use crate::config::SimulationConfig;
+use crate::error::{Result, SimulatorError};
+use crate::rng::{RngStreams, SimRng, SENSITIVITY_STREAM};
+use crate::scenario::Scenario;
+use crate::simulator::SimulationResult;
+use crate::sweep::{Axis, Sweep, SCENARIO_PARAMETER};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Run outcome whose sensitivity is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensitivityOutput {
+    /// Fraction of runs that resolved
+    ResolutionRate,
+    /// Hours from start to resolution, or the full duration if unresolved
+    TimeToResolutionHours,
+    /// Trading volume
+    Volume,
+    /// Final BSI
+    FinalBsi,
+    /// Number of trades
+    Trades,
+    /// Fees collected
+    Fees,
+}
+
+impl SensitivityOutput {
+    /// Value of the output for one run
+    pub fn value(&self, result: &SimulationResult) -> f64 {
+        match self {
+            SensitivityOutput::ResolutionRate => f64::from(u8::from(result.threshold_reached)),
+            SensitivityOutput::TimeToResolutionHours => {
+                match result.statistics.time_to_resolution {
+                    Some(secs) => secs as f64 / 3600.0,
+                    None => f64::from(result.duration_days) * 24.0,
+                }
+            }
+            SensitivityOutput::Volume => result.total_volume,
+            SensitivityOutput::FinalBsi => result.final_bsi,
+            SensitivityOutput::Trades => result.total_trades as f64,
+            SensitivityOutput::Fees => result.fees.total(),
+        }
+    }
+}
+
+/// Morris elementary effects of one parameter on one output
+///
+/// Effects are changes of the output per full range of the parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MorrisEffect {
+    /// Parameter path
+    pub parameter: String,
+    /// Output measured
+    pub output: SensitivityOutput,
+    /// Mean elementary effect
+    pub mu: f64,
+    /// Mean absolute elementary effect, the importance ranking measure
+    pub mu_star: f64,
+    /// Standard deviation of the elementary effects, high for nonlinear or
+    /// interacting parameters
+    pub sigma: f64,
+    /// Bootstrap confidence interval of `mu_star`
+    pub mu_star_interval: ConfidenceInterval,
+}
+
+/// Outcome of Morris screening
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MorrisReport {
+    /// Number of trajectories
+    pub trajectories: usize,
+    /// Design points evaluated, each with the configured replicates
+    pub evaluations: usize,
+    /// Effects by output, then parameter, in the order they were added
+    pub effects: Vec<MorrisEffect>,
+}
+
+/// Sobol indices of one parameter on one output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SobolIndex {
+    /// Parameter path
+    pub parameter: String,
+    /// Output measured
+    pub output: SensitivityOutput,
+    /// Share of output variance explained by the parameter alone
+    pub first_order: f64,
+    /// Bootstrap confidence interval of the first-order index
+    pub first_order_interval: ConfidenceInterval,
+    /// Share of output variance involving the parameter, interactions included
+    pub total_order: f64,
+    /// Bootstrap confidence interval of the total-order index
+    pub total_order_interval: ConfidenceInterval,
+}
+
+/// Outcome of Sobol analysis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SobolReport {
+    /// Number of base samples
+    pub samples: usize,
+    /// Design points evaluated, each with the configured replicates
+    pub evaluations: usize,
+    /// Indices by output, then parameter, in the order they were added
+    pub indices: Vec<SobolIndex>,
+}
+
+/// Sensitivity analysis of chosen outputs to chosen config parameters
+#[derive(Debug, Clone)]
+pub struct Sensitivity {
+    sweep: Sweep,
+    outputs: Vec<SensitivityOutput>,
+    master_seed: u64,
+    bootstrap_samples: usize,
+    confidence: f64,
+}
+
+impl Sensitivity {
+    /// Analyze runs of `scenario` around `base`
+    pub fn new(base: SimulationConfig, scenario: Scenario) -> Self {
+        Sensitivity {
+            sweep: Sweep::new(base, scenario),
+            outputs: Vec::new(),
+            master_seed: 0,
+            bootstrap_samples: 200,
+            confidence: 0.95,
+        }
+    }
+
+    /// Vary the parameter at `path` over `[min, max]`
+    pub fn parameter(mut self, path: impl Into<String>, min: f64, max: f64) -> Self {
+        self.sweep = self.sweep.vary(path, Axis::Range { min, max, steps: 1 });
+        self
+    }
+
+    /// Measure the sensitivity of `output`
+    pub fn output(mut self, output: SensitivityOutput) -> Self {
+        self.outputs.push(output);
+        self
+    }
+
+    /// Set the number of replicate runs per design point
+    pub fn replicates(mut self, replicates: usize) -> Self {
+        self.sweep = self.sweep.replicates(replicates);
+        self
+    }
+
+    /// Set the seed from which the design, replicates and bootstrap are derived
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sweep = self.sweep.seed(seed);
+        self.master_seed = seed;
+        self
+    }
+
+    /// Set the number of runs executing at once; 0 uses every available core
+    pub fn max_parallel(mut self, max_parallel: usize) -> Self {
+        self.sweep = self.sweep.max_parallel(max_parallel);
+        self
+    }
+
+    /// Set the number of bootstrap resamples behind each confidence interval
+    pub fn bootstrap_samples(mut self, samples: usize) -> Self {
+        self.bootstrap_samples = samples;
+        self
+    }
+
+    /// Set the confidence level of the intervals
+    pub fn confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        let parameters = self.sweep.parameters();
+        if parameters.is_empty() || self.outputs.is_empty() {
+            return Err(SimulatorError::InvalidConfig(
+                "Sensitivity analysis needs at least one parameter and one output".to_string(),
+            ));
+        }
+
+        if parameters.iter().any(|p| p == SCENARIO_PARAMETER) {
+            return Err(SimulatorError::InvalidConfig(
+                "The scenario cannot be varied over a range".to_string(),
+            ));
+        }
+
+        if self.bootstrap_samples == 0 || !(self.confidence > 0.0 && self.confidence < 1.0) {
+            return Err(SimulatorError::InvalidConfig(
+                "Bootstrap samples must be > 0 and confidence in (0, 1)".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Morris screening from `trajectories` trajectories on a `levels`-level grid
+    ///
+    /// Each trajectory starts at a random grid point and moves every parameter
+    /// once, in random order, by `levels / (2 * (levels - 1))` of its range.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the analysis is misconfigured, `trajectories < 2`,
+    /// `levels` is not an even number of at least 2, or a run fails
+    pub async fn morris(&self, trajectories: usize, levels: usize) -> Result<MorrisReport> {
+        self.validate()?;
+        if trajectories < 2 || levels < 2 || !levels.is_multiple_of(2) {
+            return Err(SimulatorError::InvalidConfig(
+                "Morris screening needs >= 2 trajectories and an even number of levels".to_string(),
+            ));
+        }
+
+        let k = self.sweep.parameters().len();
+        let jump = levels / 2;
+        let delta = jump as f64 / (levels - 1) as f64;
+        let unit = |grid: &[usize]| -> Vec<f64> {
+            grid.iter()
+                .map(|g| *g as f64 / (levels - 1) as f64)
+                .collect()
+        };
+
+        let mut rng = self.rng(0);
+        let mut design = Vec::with_capacity(trajectories * (k + 1));
+        let mut moves = Vec::with_capacity(trajectories * k);
+        for _ in 0..trajectories {
+            let mut grid: Vec<usize> = (0..k).map(|_| rng.gen_range(0..levels)).collect();
+            let mut order: Vec<usize> = (0..k).collect();
+            order.shuffle(&mut rng);
+            design.push(unit(&grid));
+            for parameter in order {
+                let step = if grid[parameter] + jump < levels {
+                    grid[parameter] += jump;
+                    delta
+                } else {
+                    grid[parameter] -= jump;
+                    -delta
+                };
+                design.push(unit(&grid));
+                moves.push((parameter, step));
+            }
+        }
+
+        let y = self.evaluate(&design).await?;
+
+        // effects[output][parameter][trajectory]
+        let mut effects = vec![vec![Vec::with_capacity(trajectories); k]; self.outputs.len()];
+        for (index, (parameter, step)) in moves.into_iter().enumerate() {
+            let before = &y[index + index / k];
+            let after = &y[index + index / k + 1];
+            for (output, per_parameter) in effects.iter_mut().enumerate() {
+                per_parameter[parameter].push((after[output] - before[output]) / step);
+            }
+        }
+
+        let resamples = self.resamples(trajectories, &mut self.rng(1));
+        let parameters = self.sweep.parameters();
+        let mut report = Vec::with_capacity(self.outputs.len() * k);
+        for (output, per_parameter) in self.outputs.iter().zip(&effects) {
+            for (parameter, ee) in parameters.iter().zip(per_parameter) {
+                let mu_star = |rows: &[usize]| {
+                    rows.iter().map(|r| ee[*r].abs()).sum::<f64>() / rows.len() as f64
+                };
+                let all: Vec<usize> = (0..trajectories).collect();
+                let mu = ee.iter().sum::<f64>() / trajectories as f64;
+                let sigma = (ee.iter().map(|e| (e - mu).powi(2)).sum::<f64>()
+                    / (trajectories - 1) as f64)
+                    .sqrt();
+
+                report.push(MorrisEffect {
+                    parameter: parameter.clone(),
+                    output: *output,
+                    mu,
+                    mu_star: mu_star(&all),
+                    sigma,
+                    mu_star_interval: self.interval(&resamples, mu_star),
+                });
+            }
+        }
+
+        Ok(MorrisReport {
+            trajectories,
+            evaluations: design.len(),
+            effects: report,
+        })
+    }
+
+    /// First- and total-order Sobol indices from `samples` base samples
+    ///
+    /// Uses the Saltelli design of two independent sample matrices plus one
+    /// matrix per parameter, `samples * (parameters + 2)` points in all, with
+    /// the Saltelli (2010) first-order and Jansen total-order estimators.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the analysis is misconfigured, `samples < 2`, or a
+    /// run fails
+    pub async fn sobol(&self, samples: usize) -> Result<SobolReport> {
+        self.validate()?;
+        if samples < 2 {
+            return Err(SimulatorError::InvalidConfig(
+                "Sobol analysis needs at least 2 samples".to_string(),
+            ));
+        }
+
+        let k = self.sweep.parameters().len();
+        let mut rng = self.rng(0);
+        let mut draw = || -> Vec<Vec<f64>> {
+            (0..samples)
+                .map(|_| (0..k).map(|_| rng.gen::<f64>()).collect())
+                .collect()
+        };
+        let a = draw();
+        let b = draw();
+
+        let mut design = Vec::with_capacity(samples * (k + 2));
+        design.extend(a.iter().cloned());
+        design.extend(b.iter().cloned());
+        for parameter in 0..k {
+            design.extend(a.iter().zip(&b).map(|(row_a, row_b)| {
+                let mut row = row_a.clone();
+                row[parameter] = row_b[parameter];
+                row
+            }));
+        }
+
+        let y = self.evaluate(&design).await?;
+
+        let resamples = self.resamples(samples, &mut self.rng(1));
+        let parameters = self.sweep.parameters();
+        let mut indices = Vec::with_capacity(self.outputs.len() * k);
+        for (o, output) in self.outputs.iter().enumerate() {
+            let column = |block: usize| -> Vec<f64> {
+                y[block * samples..(block + 1) * samples]
+                    .iter()
+                    .map(|row| row[o])
+                    .collect()
+            };
+            let y_a = column(0);
+            let y_b = column(1);
+            for (i, parameter) in parameters.iter().enumerate() {
+                let y_ab = column(i + 2);
+                let estimate = |rows: &[usize]| sobol_indices(&y_a, &y_b, &y_ab, rows);
+                let all: Vec<usize> = (0..samples).collect();
+                let (first_order, total_order) = estimate(&all);
+
+                indices.push(SobolIndex {
+                    parameter: parameter.clone(),
+                    output: *output,
+                    first_order,
+                    first_order_interval: self.interval(&resamples, |rows| estimate(rows).0),
+                    total_order,
+                    total_order_interval: self.interval(&resamples, |rows| estimate(rows).1),
+                });
+            }
+        }
+
+        Ok(SobolReport {
+            samples,
+            evaluations: design.len(),
+            indices,
+        })
+    }
+
+    /// Mean of each output over the replicates of every design point
+    async fn evaluate(&self, design: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+        let mut means = Vec::with_capacity(design.len());
+        for unit in design {
+            let point = self.sweep.unit_point(unit)?;
+            let mut sums = vec![0.0; self.outputs.len()];
+            let summary = self
+                .sweep
+                .run_point(&point, |result| {
+                    for (sum, output) in sums.iter_mut().zip(&self.outputs) {
+                        *sum += output.value(result);
+                    }
+                })
+                .await?;
+            let runs = summary.runs_completed.max(1) as f64;
+            means.push(sums.into_iter().map(|sum| sum / runs).collect());
+        }
+
+        Ok(means)
+    }
+
+    fn rng(&self, index: u64) -> SimRng {
+        RngStreams::new(self.master_seed).indexed_stream(SENSITIVITY_STREAM, index)
+    }
+
+    /// Bootstrap resamples of `n` design rows, shared by every estimate
+    fn resamples(&self, n: usize, rng: &mut SimRng) -> Vec<Vec<usize>> {
+        (0..self.bootstrap_samples)
+            .map(|_| (0..n).map(|_| rng.gen_range(0..n)).collect())
+            .collect()
+    }
+
+    /// Percentile bootstrap interval of `statistic`
+    fn interval(
+        &self,
+        resamples: &[Vec<usize>],
+        statistic: impl Fn(&[usize]) -> f64,
+    ) -> ConfidenceInterval {
+        let mut values: Vec<f64> = resamples.iter().map(|rows| statistic(rows)).collect();
+        values.sort_by(f64::total_cmp);
+        let tail = (1.0 - self.confidence) / 2.0;
+        ConfidenceInterval {
+            lower: quantile(&values, tail),
+            upper: quantile(&values, 1.0 - tail),
+        }
+    }
+}
+
+/// First- and total-order indices estimated from the design rows `rows`
+///
+/// An output that does not vary over the rows has both indices at 0.
+fn sobol_indices(y_a: &[f64], y_b: &[f64], y_ab: &[f64], rows: &[usize]) -> (f64, f64) {
+    let n = rows.len() as f64;
+    let mean = rows.iter().map(|r| y_a[*r] + y_b[*r]).sum::<f64>() / (2.0 * n);
+    let variance = rows
+        .iter()
+        .map(|r| (y_a[*r] - mean).powi(2) + (y_b[*r] - mean).powi(2))
+        .sum::<f64>()
+        / (2.0 * n);
+    if variance <= f64::EPSILON {
+        return (0.0, 0.0);
+    }
+
+    let first = rows
+        .iter()
+        .map(|r| y_b[*r] * (y_ab[*r] - y_a[*r]))
+        .sum::<f64>()
+        / n;
+    let total = rows
+        .iter()
+        .map(|r| (y_a[*r] - y_ab[*r]).powi(2))
+        .sum::<f64>()
+        / (2.0 * n);
+    (first / variance, total / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn analysis() -> Sensitivity {
+        let base = SimulationConfig::builder()
+            .duration_days(3)
+            .num_participants(10)
+            .initial_bsi(0.7)
+            .persistence_hours(1)
+            .build()
+            .unwrap();
+        // Participants do not move the BSI without feedback, so the crowd
+        // size has no effect on resolution
+        Sensitivity::new(base, Scenario::BullishTrend)
+            .parameter("threshold", 0.55, 0.95)
+            .parameter("num_participants", 5.0, 20.0)
+            .output(SensitivityOutput::ResolutionRate)
+            .output(SensitivityOutput::Volume)
+            .replicates(3)
+            .seed(7)
+    }
+
+    #[test]
+    fn test_sobol_estimators_recover_additive_model() {
+        // y = 4 x0 + x1: variance shares 16/17 and 1/17, no interactions
+        let mut rng = SimRng::seed_from_u64(1);
+        let n = 20_000;
+        let mut draw = || -> Vec<[f64; 2]> { (0..n).map(|_| [rng.gen(), rng.gen()]).collect() };
+        let (a, b) = (draw(), draw());
+        let model = |x: [f64; 2]| 4.0 * x[0] + x[1];
+        let y_a: Vec<f64> = a.iter().map(|x| model(*x)).collect();
+        let y_b: Vec<f64> = b.iter().map(|x| model(*x)).collect();
+        let rows: Vec<usize> = (0..n).collect();
+
+        for (i, share) in [(0, 16.0 / 17.0), (1, 1.0 / 17.0)] {
+            let y_ab: Vec<f64> = a
+                .iter()
+                .zip(&b)
+                .map(|(xa, xb)| {
+                    let mut x = *xa;
+                    x[i] = xb[i];
+                    model(x)
+                })
+                .collect();
+            let (first, total) = sobol_indices(&y_a, &y_b, &y_ab, &rows);
+            assert!(
+                (first - share).abs() < 0.05,
+                "first order {} vs {}",
+                first,
+                share
+            );
+            assert!(
+                (total - share).abs() < 0.05,
+                "total order {} vs {}",
+                total,
+                share
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_morris_separates_influential_parameters() {
+        let report = analysis().morris(4, 4).await.unwrap();
+
+        assert_eq!(report.evaluations, 12);
+        assert_eq!(report.effects.len(), 4);
+        let resolution: Vec<&MorrisEffect> = report
+            .effects
+            .iter()
+            .filter(|e| e.output == SensitivityOutput::ResolutionRate)
+            .collect();
+        assert_eq!(resolution[0].parameter, "threshold");
+        assert!(resolution[0].mu_star > 0.0);
+        assert!(resolution[0].mu_star_interval.lower <= resolution[0].mu_star);
+        assert!(resolution[0].mu_star_interval.upper >= resolution[0].mu_star);
+        assert_eq!(resolution[1].mu_star, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_sobol_reports_every_parameter_and_output() {
+        let report = analysis().sobol(4).await.unwrap();
+
+        assert_eq!(report.evaluations, 16);
+        assert_eq!(report.indices.len(), 4);
+        let crowd = &report.indices[1];
+        assert_eq!(crowd.parameter, "num_participants");
+        assert_eq!(crowd.output, SensitivityOutput::ResolutionRate);
+        assert_eq!(crowd.first_order, 0.0);
+        assert_eq!(crowd.total_order, 0.0);
+        assert!(analysis().sobol(1).await.is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Settlement and payouts for resolved or expired markets
+
+use crate::error::{Result, SimulatorError};
+use crate::market::{Market, MarketState};
+use crate::participant::Participant;
+use crate::types::PositionType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Tolerance for the conservation-of-funds check
+const CONSERVATION_TOLERANCE: f64 = 1e-6;
+
+/// How losing positions are treated at settlement
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum RefundRule {
+    /// Losing side receives nothing
+    #[default]
+    WinnerTakesAll,
+    /// Expiry voids the market and refunds every position its cost basis
+    RefundOnExpiry,
+    /// Losing side recovers a fraction of its cost basis
+    PartialRefund { fraction: f64 },
+}
+
+/// Settlement configuration
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct SettlementConfig {
+    /// Treatment of losing positions
+    pub refund_rule: RefundRule,
+}
+
+impl SettlementConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if let RefundRule::PartialRefund { fraction } = self.refund_rule {
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(SimulatorError::InvalidConfig(
+                    "Refund fraction must be between 0.0 and 1.0".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Final market outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementOutcome {
+    /// Threshold reached: long positions win
+    Resolved,
+    /// Interval ended without resolution: short positions win
+    Expired,
+}
+
+/// Payout to a single position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payout {
+    /// Participant ID
+    pub participant_id: String,
+    /// Side of the settled position
+    pub position_type: PositionType,
+    /// Contracts settled
+    pub size: f64,
+    /// Capital paid to build the position
+    pub cost_basis: f64,
+    /// Amount paid out, net of the settlement fee
+    pub payout: f64,
+    /// Settlement fee withheld from the payout
+    pub fee: f64,
+    /// Profit and loss realized at settlement, before the fee
+    pub pnl: f64,
+}
+
+/// Settlement report for a market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettlementReport {
+    /// Market outcome
+    pub outcome: SettlementOutcome,
+    /// Settlement time
+    pub settled_at: DateTime<Utc>,
+    /// Per-position payouts
+    pub payouts: Vec<Payout>,
+    /// Sum of all payouts, net of settlement fees
+    pub total_payout: f64,
+    /// Trading and settlement fees collected over the market's life
+    pub fees: f64,
+    /// Net cash held by the market before settlement
+    pub collateral: f64,
+    /// Collateral left after payouts and fees (negative = shortfall covered by the house)
+    pub house_pnl: f64,
+    /// Participant capital at creation
+    pub initial_capital: f64,
+    /// Participant capital after settlement
+    pub final_capital: f64,
+    /// `initial_capital - final_capital - house_pnl - fees`; zero when funds are conserved
+    pub conservation_error: f64,
+}
+
+impl SettlementReport {
+    /// Check that no funds were created or destroyed
+    ///
+    /// The tolerance is relative to the capital and the gross flows through
+    /// the market, since rounding error grows with the funds that changed
+    /// hands. Final balances are left out so an inflated balance cannot widen
+    /// its own tolerance.
+    pub fn is_conserved(&self) -> bool {
+        let scale = [
+            self.initial_capital,
+            self.collateral.abs(),
+            self.total_payout,
+            self.fees,
+        ]
+        .iter()
+        .fold(1.0_f64, |scale, &amount| scale.max(amount));
+        self.conservation_error.abs() <= CONSERVATION_TOLERANCE * scale
+    }
+}
+
+/// Settle all open positions and credit participants
+///
+/// # Errors
+///
+/// Returns an error if the market is still active or funds are not conserved
+pub fn settle(
+    market: &mut Market,
+    participants: &mut [Participant],
+    settled_at: DateTime<Utc>,
+    config: &SettlementConfig,
+) -> Result<SettlementReport> {
+    let outcome = match market.state {
+        MarketState::Resolved => SettlementOutcome::Resolved,
+        MarketState::Expired => SettlementOutcome::Expired,
+        state => {
+            return Err(SimulatorError::InvalidMarketState(format!(
+                "cannot settle a market in state {:?}",
+                state
+            )))
+        }
+    };
+    let winner = match outcome {
+        SettlementOutcome::Resolved => PositionType::Long,
+        SettlementOutcome::Expired => PositionType::Short,
+    };
+
+    let mut payouts = Vec::new();
+    for participant in participants.iter_mut() {
+        for position in std::mem::take(&mut participant.positions) {
+            let cost_basis = position.cost_basis();
+            let gross = match (config.refund_rule, outcome) {
+                (RefundRule::RefundOnExpiry, SettlementOutcome::Expired) => cost_basis,
+                _ if position.position_type == winner => position.size,
+                (RefundRule::PartialRefund { fraction }, _) => cost_basis * fraction,
+                _ => 0.0,
+            };
+            let fee = market.fee_schedule.settlement_fee(gross);
+            let payout = gross - fee;
+            let pnl = gross - cost_basis;
+
+            participant.capital += gross;
+            participant.pay_fee(fee);
+            participant.realized_pnl += pnl;
+            market.fees.record_settlement_fee(fee, &market.fee_schedule);
+            payouts.push(Payout {
+                participant_id: participant.id.clone(),
+                position_type: position.position_type,
+                size: position.size,
+                cost_basis,
+                payout,
+                fee,
+                pnl,
+            });
+        }
+    }
+    market.positions.clear();
+
+    let total_payout: f64 = payouts.iter().map(|p| p.payout).sum();
+    let settlement_fees: f64 = payouts.iter().map(|p| p.fee).sum();
+    let collateral = market.collateral;
+    let house_pnl = collateral - total_payout - settlement_fees;
+    let fees = market.fees.total();
+    market.realized_pnl += payouts.iter().map(|p| p.pnl).sum::<f64>();
+    market.collateral = house_pnl;
+
+    let initial_capital: f64 = participants.iter().map(|p| p.initial_capital).sum();
+    let final_capital: f64 = participants.iter().map(|p| p.capital).sum();
+    let report = SettlementReport {
+        outcome,
+        settled_at,
+        payouts,
+        total_payout,
+        fees,
+        collateral,
+        house_pnl,
+        initial_capital,
+        final_capital,
+        conservation_error: initial_capital - final_capital - house_pnl - fees,
+    };
+
+    if !report.is_conserved() {
+        return Err(SimulatorError::SimulationFailed(format!(
+            "settlement violated conservation of funds by {}",
+            report.conservation_error
+        )));
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+    use crate::participant::ParticipantBehavior;
+    use crate::rng::{RngStreams, PARTICIPANT_STREAM};
+    use crate::types::{TimeInterval, Trade, BSI};
+    use chrono::Duration;
+
+    fn setup() -> (Market, Vec<Participant>) {
+        let start = default_start_time();
+        let interval = TimeInterval::new(start, start + Duration::days(1));
+        let mut market = Market::new("m".to_string(), BSI::default(), 0.75, interval);
+        let mut rng = RngStreams::new(1).stream(PARTICIPANT_STREAM);
+
+        let mut participants = Vec::new();
+        for (i, side) in [PositionType::Long, PositionType::Short].into_iter().enumerate() {
+            let mut participant = Participant::new(
+                format!("p-{}", i),
+                ParticipantBehavior::Rational,
+                100.0,
+                &mut rng,
+            );
+            for change in participant.apply_fill(side, 10.0, 0.4, start) {
+                market.add_trade(Trade {
+                    id: format!("t-{}", i),
+                    participant_id: participant.id.clone(),
+                    trade_type: change.trade_type,
+                    position_type: change.position_type,
+                    size: change.size,
+                    price: 0.4,
+                    timestamp: start,
+                    bsi_at_trade: BSI::default(),
+                    realized_pnl: change.realized_pnl,
+                    slippage: 0.0,
+                    fee: 0.0,
+                    order_id: None,
+                    liquidity_role: None,
+                    partial_fill: false,
+                });
+            }
+            participants.push(participant);
+        }
+        (market, participants)
+    }
+
+    #[test]
+    fn test_resolved_market_pays_longs() {
+        let (mut market, mut participants) = setup();
+        market.resolve(default_start_time());
+
+        let report = settle(
+            &mut market,
+            &mut participants,
+            default_start_time(),
+            &SettlementConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(report.outcome, SettlementOutcome::Resolved);
+        assert!((participants[0].capital - 106.0).abs() < 1e-9);
+        assert!((participants[1].capital - 94.0).abs() < 1e-9);
+        assert!((report.house_pnl - 0.0).abs() < 1e-9);
+        assert!(report.is_conserved());
+    }
+
+    #[test]
+    fn test_expiry_refund_rule() {
+        let (mut market, mut participants) = setup();
+        market.expire();
+
+        let config = SettlementConfig {
+            refund_rule: RefundRule::RefundOnExpiry,
+        };
+        let report = settle(&mut market, &mut participants, default_start_time(), &config).unwrap();
+
+        assert_eq!(report.outcome, SettlementOutcome::Expired);
+        assert!(participants.iter().all(|p| (p.capital - 100.0).abs() < 1e-9));
+        assert!(report.is_conserved());
+    }
+
+    #[test]
+    fn test_conservation_tolerance_scales_with_flows() {
+        // The house paid 1e13 out through trading; the books balance up to rounding
+        let report = SettlementReport {
+            outcome: SettlementOutcome::Expired,
+            settled_at: default_start_time(),
+            payouts: Vec::new(),
+            total_payout: 0.0,
+            fees: 0.0,
+            collateral: -1e13,
+            house_pnl: -1e13,
+            initial_capital: 1_000.0,
+            final_capital: 1e13 + 1_000.0,
+            conservation_error: 100.0,
+        };
+        assert!(report.is_conserved());
+
+        // A balance inflated with no flow behind it is not rounding
+        let inflated = SettlementReport {
+            collateral: 0.0,
+            house_pnl: 0.0,
+            final_capital: 1e13,
+            conservation_error: 1_000.0 - 1e13,
+            ..report
+        };
+        assert!(!inflated.is_conserved());
+    }
+}
@@ -0,0 +1,490 @@
+//! Statistical significance of differences between groups of runs
+//!
+//! Every pair of groups is compared on each metric: continuous metrics with a
+//! Welch t-test, a Mann-Whitney U test and a two-sample Kolmogorov-Smirnov
+//! test, the resolution rate with a two-proportion z-test. P-values are
+//! corrected for multiple comparisons across the whole family of tests.
+
+use crate::simulator::SimulationResult;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+use std::collections::HashMap;
+
+/// Outcome compared between groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComparisonMetric {
+    /// Whether each run resolved
+    ResolutionRate,
+    /// Hours from start to resolution, over resolved runs only
+    TimeToResolutionHours,
+    /// Trading volume per run
+    Volume,
+    /// Final BSI per run
+    FinalBsi,
+}
+
+impl ComparisonMetric {
+    /// Every metric, in comparison order
+    pub fn all() -> [ComparisonMetric; 4] {
+        [
+            ComparisonMetric::ResolutionRate,
+            ComparisonMetric::TimeToResolutionHours,
+            ComparisonMetric::Volume,
+            ComparisonMetric::FinalBsi,
+        ]
+    }
+
+    /// Samples of the metric, one per run it applies to
+    pub fn samples(&self, results: &[SimulationResult]) -> Vec<f64> {
+        match self {
+            ComparisonMetric::ResolutionRate => results
+                .iter()
+                .map(|r| f64::from(u8::from(r.threshold_reached)))
+                .collect(),
+            ComparisonMetric::TimeToResolutionHours => results
+                .iter()
+                .filter_map(|r| r.statistics.time_to_resolution)
+                .map(|secs| secs as f64 / 3600.0)
+                .collect(),
+            ComparisonMetric::Volume => results.iter().map(|r| r.total_volume).collect(),
+            ComparisonMetric::FinalBsi => results.iter().map(|r| r.final_bsi).collect(),
+        }
+    }
+
+    /// Tests applied to the metric
+    fn tests(&self) -> &'static [SignificanceTest] {
+        match self {
+            ComparisonMetric::ResolutionRate => &[SignificanceTest::TwoProportionZ],
+            _ => &[
+                SignificanceTest::WelchT,
+                SignificanceTest::MannWhitneyU,
+                SignificanceTest::KolmogorovSmirnov,
+            ],
+        }
+    }
+}
+
+/// Two-sample hypothesis test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignificanceTest {
+    /// Difference in means without assuming equal variances; effect size is Cohen's d
+    WelchT,
+    /// Difference in location by ranks; effect size is the rank-biserial correlation
+    MannWhitneyU,
+    /// Difference anywhere in the distributions; effect size is the statistic D
+    KolmogorovSmirnov,
+    /// Difference in proportions; effect size is Cohen's h
+    TwoProportionZ,
+}
+
+/// Multiple-comparison correction of p-values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Correction {
+    /// Raw p-values
+    None,
+    /// Bonferroni family-wise error control
+    Bonferroni,
+    /// Holm step-down family-wise error control
+    Holm,
+    /// Benjamini-Hochberg false discovery rate control
+    BenjaminiHochberg,
+}
+
+impl Correction {
+    /// Adjusted p-values, in the order of `p_values`
+    pub fn adjust(&self, p_values: &[f64]) -> Vec<f64> {
+        let m = p_values.len() as f64;
+        let mut order: Vec<usize> = (0..p_values.len()).collect();
+        order.sort_by(|a, b| p_values[*a].total_cmp(&p_values[*b]));
+        let mut adjusted = p_values.to_vec();
+
+        match self {
+            Correction::None => {}
+            Correction::Bonferroni => {
+                for p in &mut adjusted {
+                    *p = (*p * m).min(1.0);
+                }
+            }
+            Correction::Holm => {
+                let mut running = 0.0_f64;
+                for (rank, &i) in order.iter().enumerate() {
+                    running = running.max((p_values[i] * (m - rank as f64)).min(1.0));
+                    adjusted[i] = running;
+                }
+            }
+            Correction::BenjaminiHochberg => {
+                let mut running = 1.0_f64;
+                for (rank, &i) in order.iter().enumerate().rev() {
+                    running = running.min(p_values[i] * m / (rank + 1) as f64);
+                    adjusted[i] = running;
+                }
+            }
+        }
+
+        adjusted
+    }
+}
+
+/// One test between two groups on one metric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairwiseTest {
+    /// First group
+    pub first: String,
+    /// Second group
+    pub second: String,
+    /// Metric compared
+    pub metric: ComparisonMetric,
+    /// Test applied
+    pub test: SignificanceTest,
+    /// Test statistic (t, U of the first group, D or z)
+    pub statistic: f64,
+    /// Two-sided p-value
+    pub p_value: f64,
+    /// P-value after the matrix's correction
+    pub adjusted_p_value: f64,
+    /// Effect size, signed so that positive means the first group is larger
+    /// (always non-negative for the Kolmogorov-Smirnov D)
+    pub effect_size: f64,
+    /// Whether the adjusted p-value is below the significance level
+    pub significant: bool,
+}
+
+/// Pairwise tests between every pair of groups
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonMatrix {
+    /// Group names, sorted
+    pub groups: Vec<String>,
+    /// Significance level
+    pub alpha: f64,
+    /// Correction applied across all tests
+    pub correction: Correction,
+    /// Tests by pair of groups in sorted order, then metric, then test
+    pub tests: Vec<PairwiseTest>,
+}
+
+impl ComparisonMatrix {
+    /// Compare every pair of groups on every metric
+    ///
+    /// Samples too small for a test (fewer than two runs for the Welch test,
+    /// or no runs) give a statistic of 0 and a p-value of 1.
+    pub fn new(
+        results_by_group: &HashMap<String, Vec<SimulationResult>>,
+        alpha: f64,
+        correction: Correction,
+    ) -> Self {
+        let mut groups: Vec<&String> = results_by_group.keys().collect();
+        groups.sort();
+
+        let mut tests = Vec::new();
+        for (i, first) in groups.iter().enumerate() {
+            for second in &groups[i + 1..] {
+                for metric in ComparisonMetric::all() {
+                    let x = metric.samples(&results_by_group[*first]);
+                    let y = metric.samples(&results_by_group[*second]);
+                    for test in metric.tests() {
+                        let outcome = match test {
+                            SignificanceTest::WelchT => welch_t(&x, &y),
+                            SignificanceTest::MannWhitneyU => mann_whitney_u(&x, &y),
+                            SignificanceTest::KolmogorovSmirnov => kolmogorov_smirnov(&x, &y),
+                            SignificanceTest::TwoProportionZ => two_proportion_z(&x, &y),
+                        };
+                        tests.push(PairwiseTest {
+                            first: (*first).clone(),
+                            second: (*second).clone(),
+                            metric,
+                            test: *test,
+                            statistic: outcome.statistic,
+                            p_value: outcome.p_value,
+                            adjusted_p_value: outcome.p_value,
+                            effect_size: outcome.effect_size,
+                            significant: false,
+                        });
+                    }
+                }
+            }
+        }
+
+        let p_values: Vec<f64> = tests.iter().map(|t| t.p_value).collect();
+        for (test, adjusted) in tests.iter_mut().zip(correction.adjust(&p_values)) {
+            test.adjusted_p_value = adjusted;
+            test.significant = adjusted < alpha;
+        }
+
+        ComparisonMatrix {
+            groups: groups.into_iter().cloned().collect(),
+            alpha,
+            correction,
+            tests,
+        }
+    }
+
+    /// Test between two groups, in either order, on a metric
+    pub fn get(
+        &self,
+        first: &str,
+        second: &str,
+        metric: ComparisonMetric,
+        test: SignificanceTest,
+    ) -> Option<&PairwiseTest> {
+        self.tests.iter().find(|t| {
+            t.metric == metric
+                && t.test == test
+                && ((t.first == first && t.second == second)
+                    || (t.first == second && t.second == first))
+        })
+    }
+}
+
+/// Statistic, p-value and effect size of one test
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TestOutcome {
+    statistic: f64,
+    p_value: f64,
+    effect_size: f64,
+}
+
+impl TestOutcome {
+    const UNTESTABLE: TestOutcome = TestOutcome {
+        statistic: 0.0,
+        p_value: 1.0,
+        effect_size: 0.0,
+    };
+}
+
+fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+fn variance(samples: &[f64]) -> f64 {
+    let m = mean(samples);
+    samples.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (samples.len() - 1) as f64
+}
+
+/// Two-sided p-value of a standard normal statistic
+fn normal_p_value(z: f64) -> f64 {
+    let normal = Normal::new(0.0, 1.0).expect("standard normal is valid");
+    2.0 * (1.0 - normal.cdf(z.abs()))
+}
+
+/// Statistic and p-value when the standard error vanishes
+fn degenerate(difference: f64) -> TestOutcome {
+    if difference == 0.0 {
+        return TestOutcome::UNTESTABLE;
+    }
+    TestOutcome {
+        statistic: f64::INFINITY.copysign(difference),
+        p_value: 0.0,
+        effect_size: 0.0,
+    }
+}
+
+fn welch_t(x: &[f64], y: &[f64]) -> TestOutcome {
+    if x.len() < 2 || y.len() < 2 {
+        return TestOutcome::UNTESTABLE;
+    }
+
+    let (nx, ny) = (x.len() as f64, y.len() as f64);
+    let (vx, vy) = (variance(x), variance(y));
+    let difference = mean(x) - mean(y);
+    let se2 = vx / nx + vy / ny;
+    if se2 <= 0.0 {
+        return degenerate(difference);
+    }
+
+    let t = difference / se2.sqrt();
+    let df = se2.powi(2) / ((vx / nx).powi(2) / (nx - 1.0) + (vy / ny).powi(2) / (ny - 1.0));
+    let p_value = StudentsT::new(0.0, 1.0, df)
+        .map(|dist| 2.0 * (1.0 - dist.cdf(t.abs())))
+        .unwrap_or(1.0);
+    let pooled = (((nx - 1.0) * vx + (ny - 1.0) * vy) / (nx + ny - 2.0)).sqrt();
+
+    TestOutcome {
+        statistic: t,
+        p_value,
+        effect_size: difference / pooled,
+    }
+}
+
+/// Normal approximation with tie correction and continuity correction
+fn mann_whitney_u(x: &[f64], y: &[f64]) -> TestOutcome {
+    if x.is_empty() || y.is_empty() {
+        return TestOutcome::UNTESTABLE;
+    }
+
+    // Average ranks over the pooled sample, first group flagged
+    let mut pooled: Vec<(f64, bool)> = x
+        .iter()
+        .map(|v| (*v, true))
+        .chain(y.iter().map(|v| (*v, false)))
+        .collect();
+    pooled.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let n = pooled.len() as f64;
+    let mut rank_sum = 0.0;
+    let mut tie_term = 0.0;
+    let mut start = 0;
+    while start < pooled.len() {
+        let end = start
+            + pooled[start..]
+                .iter()
+                .take_while(|(v, _)| *v == pooled[start].0)
+                .count();
+        let rank = (start + end + 1) as f64 / 2.0;
+        rank_sum += rank
+            * pooled[start..end]
+                .iter()
+                .filter(|(_, first)| *first)
+                .count() as f64;
+        let ties = (end - start) as f64;
+        tie_term += ties.powi(3) - ties;
+        start = end;
+    }
+
+    let (nx, ny) = (x.len() as f64, y.len() as f64);
+    let u = rank_sum - nx * (nx + 1.0) / 2.0;
+    let centered = u - nx * ny / 2.0;
+    let sigma = (nx * ny / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)))).sqrt();
+    let effect_size = 2.0 * u / (nx * ny) - 1.0;
+    if sigma <= 0.0 {
+        return TestOutcome {
+            effect_size,
+            ..TestOutcome::UNTESTABLE
+        };
+    }
+
+    let z = (centered.abs() - 0.5).max(0.0) / sigma;
+    TestOutcome {
+        statistic: u,
+        p_value: normal_p_value(z),
+        effect_size,
+    }
+}
+
+/// Asymptotic Kolmogorov distribution with the Stephens small-sample correction
+fn kolmogorov_smirnov(x: &[f64], y: &[f64]) -> TestOutcome {
+    if x.is_empty() || y.is_empty() {
+        return TestOutcome::UNTESTABLE;
+    }
+
+    let mut xs = x.to_vec();
+    let mut ys = y.to_vec();
+    xs.sort_by(f64::total_cmp);
+    ys.sort_by(f64::total_cmp);
+    let (nx, ny) = (xs.len() as f64, ys.len() as f64);
+
+    let (mut i, mut j, mut d) = (0, 0, 0.0_f64);
+    while i < xs.len() && j < ys.len() {
+        let value = xs[i].min(ys[j]);
+        while i < xs.len() && xs[i] == value {
+            i += 1;
+        }
+        while j < ys.len() && ys[j] == value {
+            j += 1;
+        }
+        d = d.max((i as f64 / nx - j as f64 / ny).abs());
+    }
+
+    let en = (nx * ny / (nx + ny)).sqrt();
+    let lambda = (en + 0.12 + 0.11 / en) * d;
+    TestOutcome {
+        statistic: d,
+        p_value: kolmogorov_survival(lambda),
+        effect_size: d,
+    }
+}
+
+/// P(K > lambda) for the Kolmogorov distribution
+fn kolmogorov_survival(lambda: f64) -> f64 {
+    if lambda < 1e-3 {
+        return 1.0;
+    }
+
+    let mut sum = 0.0;
+    for j in 1..=100 {
+        let term = (-2.0 * (j as f64 * lambda).powi(2)).exp();
+        sum += if j % 2 == 1 { term } else { -term };
+        if term < 1e-12 {
+            break;
+        }
+    }
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+/// Pooled two-proportion z-test on 0/1 samples
+fn two_proportion_z(x: &[f64], y: &[f64]) -> TestOutcome {
+    if x.is_empty() || y.is_empty() {
+        return TestOutcome::UNTESTABLE;
+    }
+
+    let (nx, ny) = (x.len() as f64, y.len() as f64);
+    let (px, py) = (mean(x), mean(y));
+    let pooled = (px * nx + py * ny) / (nx + ny);
+    let se = (pooled * (1.0 - pooled) * (1.0 / nx + 1.0 / ny)).sqrt();
+    let effect_size = 2.0 * px.sqrt().asin() - 2.0 * py.sqrt().asin();
+    if se <= 0.0 {
+        return TestOutcome {
+            effect_size,
+            ..degenerate(px - py)
+        };
+    }
+
+    let z = (px - py) / se;
+    TestOutcome {
+        statistic: z,
+        p_value: normal_p_value(z),
+        effect_size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn test_two_sample_tests() {
+        let welch = welch_t(&[1.0, 2.0, 3.0, 4.0, 5.0], &[2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert!(close(welch.statistic, -1.8974, 1e-4));
+        assert!(welch.p_value > 0.1 && welch.p_value < 0.12);
+        assert!(close(welch.effect_size, -1.2, 1e-9));
+
+        let mwu = mann_whitney_u(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]);
+        assert_eq!(mwu.statistic, 0.0);
+        assert_eq!(mwu.effect_size, -1.0);
+
+        let same = kolmogorov_smirnov(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]);
+        assert_eq!((same.statistic, same.p_value), (0.0, 1.0));
+        let apart: Vec<f64> = (0..30).map(f64::from).collect();
+        let shifted: Vec<f64> = apart.iter().map(|v| v + 100.0).collect();
+        let disjoint = kolmogorov_smirnov(&apart, &shifted);
+        assert_eq!(disjoint.statistic, 1.0);
+        assert!(disjoint.p_value < 1e-6);
+
+        let successes =
+            |k: usize| -> Vec<f64> { (0..100).map(|i| f64::from(u8::from(i < k))).collect() };
+        let proportions = two_proportion_z(&successes(50), &successes(30));
+        assert!(close(proportions.statistic, 2.8868, 1e-4));
+        assert!(close(proportions.p_value, 0.00389, 1e-5));
+    }
+
+    #[test]
+    fn test_corrections() {
+        let p = [0.01, 0.04, 0.03];
+
+        assert_eq!(Correction::None.adjust(&p), p.to_vec());
+        let bonferroni = Correction::Bonferroni.adjust(&p);
+        let holm = Correction::Holm.adjust(&p);
+        let bh = Correction::BenjaminiHochberg.adjust(&p);
+        for (actual, expected) in [
+            (bonferroni, [0.03, 0.12, 0.09]),
+            (holm, [0.03, 0.06, 0.06]),
+            (bh, [0.03, 0.04, 0.04]),
+        ] {
+            for (a, e) in actual.iter().zip(expected) {
+                assert!(close(*a, e, 1e-12), "{:?} vs {:?}", actual, expected);
+            }
+        }
+    }
+}
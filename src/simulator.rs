@@ -1,131 +1,622 @@
 //! Main simulator implementation
 
+use crate::agent::{Agent, AgentAction, AgentObservation, BehaviorAgent, StrategyAgent};
+use crate::clock::{default_start_time, SimClock};
 use crate::config::SimulationConfig;
 use crate::error::{Result, SimulatorError};
+use crate::feedback::{FeedbackModel, FeedbackStatistics};
+use crate::fees::FeeLedger;
+use crate::history::{BsiSeries, ReplayOracle};
 use crate::market::{Market, MarketState};
-use crate::oracle::{OracleConfig, OracleSimulator};
+use crate::network::{OracleNetwork, OracleNetworkStatistics};
+use crate::oracle::{OracleConfig, OracleObserver, OracleSimulator};
+use crate::orderbook::{Execution, OrderBook, OrderSide, VenueConfig};
 use crate::participant::{Participant, ParticipantBehavior};
-use crate::scenario::Scenario;
-use crate::types::{BSI, TimeInterval, Trade, TradeType};
+use crate::resolution::{ResolutionAccuracy, RuleStatus};
+use crate::rng::{
+    RngStreams, SimRng, OBSERVATION_STREAM, ORACLE_STREAM, PARTICIPANT_STREAM, SCENARIO_STREAM,
+};
+use crate::scenario::{
+    hours_to_duration, Scenario, ScenarioAction, ScenarioDefinition, ScenarioTimeline,
+};
+use crate::settlement::{settle, SettlementReport};
+use crate::strategy::{EquityPoint, PositionSizing, StrategyBacktest, TradingStrategy};
+use crate::timeseries::{TickSnapshot, TimeSeries, TimeSeriesRecorder};
+use crate::types::{LiquidityRole, PositionType, Trade, BSI};
 use chrono::{DateTime, Duration, Utc};
-use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Main simulator
 pub struct Simulator {
     config: SimulationConfig,
+    agents: Vec<Box<dyn Agent>>,
+    strategy: Option<(StrategyAgent, f64)>,
+    replay: Option<BsiSeries>,
+}
+
+/// Capital given to each simulated participant
+const PARTICIPANT_CAPITAL: f64 = 1000.0;
+
+/// Risk tolerance of accounts driven by added agents
+const AGENT_RISK_TOLERANCE: f64 = 0.5;
+
+/// Participant ID of the strategy under test
+const STRATEGY_PARTICIPANT_ID: &str = "strategy";
+
+/// Participant accounts of a run, addressable by ID
+struct Accounts {
+    participants: Vec<Participant>,
+    index: HashMap<String, usize>,
+}
+
+impl Accounts {
+    fn new(participants: Vec<Participant>) -> Self {
+        let index = participants
+            .iter()
+            .enumerate()
+            .map(|(index, participant)| (participant.id.clone(), index))
+            .collect();
+        Accounts {
+            participants,
+            index,
+        }
+    }
+
+    fn push(&mut self, participant: Participant) {
+        self.index
+            .insert(participant.id.clone(), self.participants.len());
+        self.participants.push(participant);
+    }
+}
+
+/// Source of the BSI the market sees
+enum Reporter {
+    Single(OracleObserver),
+    Network(OracleNetwork),
+}
+
+impl Reporter {
+    fn observe(&mut self, latent: BSI, rng: &mut SimRng) -> Result<BSI> {
+        match self {
+            Reporter::Single(observer) => observer.observe(latent, rng),
+            Reporter::Network(network) => network.observe(latent, rng),
+        }
+    }
+}
+
+/// Source of the latent belief
+enum LatentBelief {
+    Simulated(OracleSimulator),
+    Replay(ReplayOracle),
+}
+
+impl LatentBelief {
+    fn next_bsi(&mut self, rng: &mut SimRng) -> Result<BSI> {
+        match self {
+            LatentBelief::Simulated(oracle) => oracle.next_bsi(rng),
+            LatentBelief::Replay(replay) => replay.next_bsi(),
+        }
+    }
+
+    fn current_bsi(&self) -> BSI {
+        match self {
+            LatentBelief::Simulated(oracle) => oracle.current_bsi(),
+            LatentBelief::Replay(replay) => replay.current_bsi(),
+        }
+    }
+
+    /// Scenario targets, shocks and feedback only move a simulated belief
+    fn oracle(&mut self) -> Option<&mut OracleSimulator> {
+        match self {
+            LatentBelief::Simulated(oracle) => Some(oracle),
+            LatentBelief::Replay(_) => None,
+        }
+    }
+}
+
+/// Per-run record of the strategy under test
+struct StrategyRun {
+    index: usize,
+    trades: Vec<Trade>,
+    equity_curve: Vec<EquityPoint>,
 }
 
 impl Simulator {
     /// Create a new simulator
     pub fn new(config: SimulationConfig) -> Self {
-        Simulator { config }
+        Simulator {
+            config,
+            agents: Vec::new(),
+            strategy: None,
+            replay: None,
+        }
+    }
+
+    /// Replay a historical series as the latent belief in every run
+    ///
+    /// The series is resampled to `update_frequency_secs` and runs start at
+    /// its first record, holding the last value once it is exhausted.
+    /// Scenario targets, shocks and trading feedback do not move a replayed
+    /// belief; observation noise, delay and bias still apply.
+    pub fn with_replay(mut self, series: BsiSeries) -> Self {
+        self.replay = Some(series);
+        self
+    }
+
+    /// Add an agent that trades in every run alongside the built-in crowd
+    ///
+    /// Each run starts from a fresh clone of the agent, trading its own
+    /// account funded like the other participants.
+    pub fn with_agent(mut self, agent: Box<dyn Agent>) -> Self {
+        self.agents.push(agent);
+        self
+    }
+
+    /// Trade `strategy` in every run as one agent among the crowd
+    ///
+    /// The strategy trades with market orders sized by `sizing`, and its
+    /// performance is reported in [`SimulationResult::strategy`].
+    pub fn with_strategy(
+        mut self,
+        strategy: Box<dyn TradingStrategy>,
+        sizing: PositionSizing,
+    ) -> Self {
+        let risk_tolerance = sizing.max_position_fraction;
+        self.strategy = Some((StrategyAgent::new(strategy, sizing), risk_tolerance));
+        self
     }
 
     /// Run simulation with given scenario
     pub async fn run(&self, scenario: Scenario) -> Result<SimulationResult> {
-        // Initialize market
-        let start_time = Utc::now();
-        let end_time = start_time + Duration::days(self.config.duration_days as i64);
-        let interval = TimeInterval::new(start_time, end_time);
+        self.run_timeline(scenario, &scenario.definition(), self.config.seed).await
+    }
+
+    /// Run simulation with given scenario, overriding the configured seed
+    pub async fn run_with_seed(&self, scenario: Scenario, seed: u64) -> Result<SimulationResult> {
+        self.run_timeline(scenario, &scenario.definition(), Some(seed)).await
+    }
+
+    /// Run simulation with a custom scenario timeline
+    ///
+    /// The result is reported as [`Scenario::Custom`] under the definition's name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the definition is invalid or the run fails
+    pub async fn run_custom(&self, definition: &ScenarioDefinition) -> Result<SimulationResult> {
+        definition.validate()?;
+        self.run_timeline(Scenario::Custom, definition, self.config.seed).await
+    }
+
+    /// Run a scenario timeline from `seed`, or from entropy if absent
+    pub(crate) async fn run_timeline(
+        &self,
+        scenario: Scenario,
+        definition: &ScenarioDefinition,
+        seed: Option<u64>,
+    ) -> Result<SimulationResult> {
+        // Derive independent random streams from the master seed
+        let streams = RngStreams::from_seed_or_entropy(seed);
+        let mut oracle_rng = streams.stream(ORACLE_STREAM);
+        let mut participant_rng = streams.stream(PARTICIPANT_STREAM);
+        let mut scenario_rng = streams.stream(SCENARIO_STREAM);
+        let mut observation_rng = streams.stream(OBSERVATION_STREAM);
+
+        // Initialize the virtual clock and market interval
+        let mut clock = self.create_clock();
+        let start_time = clock.start();
+        let interval = clock.interval(Duration::days(self.config.duration_days as i64));
+        let end_time = interval.end;
 
         let initial_bsi = BSI::new(self.config.initial_bsi)
-            .map_err(|e| SimulatorError::InvalidConfig(e))?;
+            .map_err(SimulatorError::InvalidConfig)?;
         let mut market = Market::new(
-            format!("sim-{}", start_time.timestamp()),
+            format!("sim-{}-{:016x}", start_time.timestamp(), streams.master_seed()),
             initial_bsi,
             self.config.threshold,
             interval,
-        );
+        )
+        .with_resolution_rule(
+            self.config
+                .resolution_rule
+                .build(self.config.threshold, self.config.persistence_hours),
+        )
+        .with_pricing(self.config.pricing.build(initial_bsi))
+        .with_fees(self.config.fees);
+        if let VenueConfig::OrderBook(_) = self.config.venue {
+            market = market.with_order_book(OrderBook::new());
+        }
 
-        // Initialize oracle
-        let oracle_config = OracleConfig {
-            update_frequency: self.config.update_frequency_secs,
-            noise_level: self.config.volatility * 0.5,
-            drift_rate: self.config.volatility * 0.1,
-            mean_reversion: 0.1,
+        // Initialize the latent belief and the oracle's view of it
+        let oracle_config = self.oracle_config();
+        let mut reporter = match &self.config.oracle_network {
+            Some(network) => Reporter::Network(OracleNetwork::new(network.clone())?),
+            None => Reporter::Single(OracleObserver::new(&oracle_config)?),
+        };
+        let mut oracle = match &self.replay {
+            Some(series) => LatentBelief::Replay(ReplayOracle::new(series, clock.tick())?),
+            None => LatentBelief::Simulated(OracleSimulator::new(
+                oracle_config.clone(),
+                initial_bsi,
+            )),
         };
 
-        let mut oracle = OracleSimulator::new(oracle_config, initial_bsi);
+        // Track when the latent belief alone would satisfy the resolution rule
+        let mut latent_rule = self
+            .config
+            .resolution_rule
+            .build(self.config.threshold, self.config.persistence_hours);
+        let mut latent_resolution_time = None;
 
-        // Set oracle target based on scenario
-        match scenario {
-            Scenario::BullishTrend => oracle.set_target(0.8),
-            Scenario::BearishTrend => oracle.set_target(0.2),
-            Scenario::SentimentReversal => oracle.set_target(0.9),
-            Scenario::ConsensusFormation => oracle.set_target(self.config.threshold),
-            Scenario::ParabolicRise => oracle.set_target(0.95),
-            _ => {}
-        }
+        let mut timeline = ScenarioTimeline::new(definition);
+        let mut paused_until = None;
+        let mut influx = 0;
 
-        // Initialize participants
-        let mut participants = self.create_participants();
+        // Initialize participants and the agents trading for them
+        let mut participants = self.create_participants(&mut participant_rng);
+        let mut agents: Vec<Box<dyn Agent>> = participants
+            .iter()
+            .map(|p| Box::new(BehaviorAgent::new(p.behavior)) as Box<dyn Agent>)
+            .collect();
+        for (i, agent) in self.agents.iter().enumerate() {
+            participants.push(Participant::with_risk_tolerance(
+                format!("agent-{}", i),
+                ParticipantBehavior::Rational,
+                PARTICIPANT_CAPITAL,
+                AGENT_RISK_TOLERANCE,
+            ));
+            agents.push(agent.clone());
+        }
+        let mut strategy_run = self.strategy.as_ref().map(|(agent, risk_tolerance)| {
+            participants.push(Participant::with_risk_tolerance(
+                STRATEGY_PARTICIPANT_ID.to_string(),
+                ParticipantBehavior::Rational,
+                PARTICIPANT_CAPITAL,
+                *risk_tolerance,
+            ));
+            agents.push(Box::new(agent.clone()));
+            StrategyRun {
+                index: agents.len() - 1,
+                trades: Vec::new(),
+                equity_curve: Vec::new(),
+            }
+        });
+        let mut accounts = Accounts::new(participants);
 
         // Simulation loop
-        let mut current_time = start_time;
-        let update_interval = Duration::seconds(self.config.update_frequency_secs as i64);
         let mut trade_counter = 0;
+        let mut recorder = self.config.time_series.map(TimeSeriesRecorder::new);
+        let mut feedback = self.config.feedback.map(FeedbackModel::new);
+
+        while clock.now() < end_time && market.is_open() {
+            let current_time = clock.now();
+            let trades_before = market.trades.len();
+            let volume_before = market.total_volume;
+
+            if paused_until.is_some_and(|until| current_time >= until) {
+                paused_until = None;
+                market.resume();
+            }
 
-        while current_time < end_time && market.state == MarketState::Active {
-            // Update BSI
-            let new_bsi = oracle.next_bsi()?;
-            market.update_bsi(new_bsi);
-
-            // Apply scenario-specific events
-            if let Some(shock) = self.should_apply_shock(&scenario, current_time, start_time) {
-                oracle.apply_shock(shock)?;
-            }
-
-            // Simulate participant trading
-            for participant in &mut participants {
-                if participant.should_trade(new_bsi, self.config.threshold) {
-                    let trade = self.create_trade(
-                        participant,
-                        new_bsi,
-                        current_time,
-                        &mut trade_counter,
-                    );
+            // Fire the scenario events due before this update
+            let mut shock = None;
+            let bsi = market.current_bsi;
+            for action in timeline.due(current_time, start_time, bsi, &mut scenario_rng) {
+                match action {
+                    ScenarioAction::PauseTrading { hours } => {
+                        // A pause past the end of the run lasts until the end
+                        let until = current_time
+                            .checked_add_signed(hours_to_duration(hours))
+                            .map_or(end_time, |until| until.min(end_time));
+                        paused_until = Some(until);
+                        market.pause();
+                    }
+                    ScenarioAction::ParticipantInflux { count, behavior } => {
+                        let behaviors = ParticipantBehavior::all();
+                        for _ in 0..count {
+                            let behavior =
+                                behavior.unwrap_or(behaviors[influx % behaviors.len()]);
+                            accounts.push(Participant::new(
+                                format!("influx-{}", influx),
+                                behavior,
+                                PARTICIPANT_CAPITAL,
+                                &mut participant_rng,
+                            ));
+                            agents.push(Box::new(BehaviorAgent::new(behavior)));
+                            influx += 1;
+                        }
+                    }
+                    action => {
+                        // Targets, shocks and regimes only move a simulated belief
+                        let Some(oracle) = oracle.oracle() else {
+                            continue;
+                        };
+                        match action {
+                            ScenarioAction::SetTarget { target } => oracle.set_target(target),
+                            ScenarioAction::TargetThreshold => {
+                                oracle.set_target(self.config.threshold)
+                            }
+                            ScenarioAction::ClearTarget => oracle.clear_target(),
+                            ScenarioAction::Shock { magnitude } => {
+                                oracle.apply_shock(magnitude)?;
+                                *shock.get_or_insert(0.0) += magnitude;
+                            }
+                            ScenarioAction::VolatilityRegime { multiplier } => {
+                                oracle.set_volatility_multiplier(multiplier)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            // Evolve the latent belief and report the oracle's observation of it
+            let latent_bsi = oracle.next_bsi(&mut oracle_rng)?;
+            if latent_rule.observe(latent_bsi, current_time) == RuleStatus::Met {
+                latent_resolution_time.get_or_insert(current_time);
+            }
+            let new_bsi = reporter.observe(latent_bsi, &mut observation_rng)?;
+            market.update_bsi(new_bsi, current_time);
+
+            // Expire resting orders that outlived their time-to-live
+            if let VenueConfig::OrderBook(book_config) = &self.config.venue {
+                let ttl = clock.tick() * book_config.order_ttl_ticks as i32;
+                if let Some(book) = market.order_book.as_mut() {
+                    book.expire_before(current_time - ttl);
+                }
+            }
+
+            // Let every agent observe the market and trade, unless trading is paused
+            let order_book = match &self.config.venue {
+                VenueConfig::Instant => None,
+                VenueConfig::OrderBook(book_config) => Some(book_config),
+            };
+            for (index, agent) in agents.iter_mut().enumerate() {
+                if market.state != MarketState::Active {
+                    break;
+                }
+                let observation = AgentObservation {
+                    timestamp: current_time,
+                    market: &market,
+                    participant: &accounts.participants[index],
+                    order_book,
+                };
+                let action = agent.decide(&observation, &mut participant_rng);
+                let trades = self.execute_action(
+                    index,
+                    action,
+                    &mut accounts,
+                    &mut market,
+                    current_time,
+                    &mut trade_counter,
+                )?;
+                for trade in trades {
                     market.add_trade(trade);
                 }
             }
 
+            // Report this tick's fills to the agents that traded
+            for trade in &market.trades[trades_before..] {
+                agents[accounts.index[&trade.participant_id]].on_fill(trade);
+            }
+
+            if let Some(run) = strategy_run.as_mut() {
+                let trader = &accounts.participants[run.index];
+                run.trades.extend(
+                    market.trades[trades_before..]
+                        .iter()
+                        .filter(|trade| trade.participant_id == trader.id)
+                        .cloned(),
+                );
+                run.equity_curve.push(EquityPoint {
+                    timestamp: current_time,
+                    equity: trader.equity(market.market_price()),
+                    position: trader.net_position(),
+                });
+            }
+
+            if let Some(book) = market.order_book.as_mut() {
+                book.sample();
+            }
+
+            // Feed this tick's trading into a later oracle update
+            if let Some(feedback) = feedback.as_mut() {
+                let trades = &market.trades[trades_before..];
+                let adjustment = feedback.observe(new_bsi, market.market_price(), trades);
+                if let Some(oracle) = oracle.oracle() {
+                    oracle.add_feedback(adjustment);
+                }
+            }
+
             // Check for resolution
-            if market.should_resolve(current_time) {
+            if market.should_resolve(&clock) {
                 market.resolve(current_time);
+            }
+
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.record(TickSnapshot {
+                    timestamp: current_time,
+                    bsi: new_bsi.value(),
+                    latent_bsi: latent_bsi.value(),
+                    market_price: market.market_price(),
+                    shock_applied: shock,
+                    trades: market.trades.len() - trades_before,
+                    volume: market.total_volume - volume_before,
+                    open_interest: market.open_interest(),
+                    state: market.state,
+                    ticks: 1,
+                });
+            }
+
+            if !market.is_open() {
                 break;
             }
 
-            current_time = current_time + update_interval;
+            clock.advance().await;
         }
 
+        // Expire the market if the interval ended without resolution
+        if market.is_open() {
+            market.expire();
+        }
+
+        // Snapshot trading state at close, then pay out open positions
+        let statistics = market.statistics();
+        let participant_pnl = accounts
+            .participants
+            .iter()
+            .zip(&agents)
+            .map(|(p, agent)| ParticipantPnl::new(p, agent.name(), market.market_price()))
+            .collect();
+        let settled_at = market.resolution_time.unwrap_or(end_time);
+        let settlement = settle(
+            &mut market,
+            &mut accounts.participants,
+            settled_at,
+            &self.config.settlement,
+        )?;
+
+        let mut settlement_pnl = vec![0.0; agents.len()];
+        for payout in &settlement.payouts {
+            settlement_pnl[accounts.index[&payout.participant_id]] += payout.pnl;
+        }
+        for (agent, pnl) in agents.iter_mut().zip(settlement_pnl) {
+            agent.on_settlement(settlement.outcome, pnl);
+        }
+
+        let strategy = strategy_run.map(|mut run| {
+            let trader = &accounts.participants[run.index];
+            run.equity_curve.push(EquityPoint {
+                timestamp: settled_at,
+                equity: trader.capital,
+                position: 0.0,
+            });
+            StrategyBacktest::from_equity_curve(
+                agents[run.index].name(),
+                trader.initial_capital,
+                trader.fees_paid,
+                settlement.outcome,
+                run.equity_curve,
+                run.trades,
+            )
+        });
+
         // Generate result
         let result = SimulationResult {
             market_id: market.id.clone(),
+            seed: streams.master_seed(),
             scenario,
+            scenario_name: definition.name.clone(),
+            resolution_rule: market.resolution_rule_name().to_string(),
             final_bsi: market.current_bsi.value(),
             total_volume: market.total_volume,
             total_trades: market.trades.len(),
+            first_crossing_time: market.first_crossing_time,
             resolution_time: market.resolution_time,
-            duration_days: (current_time - start_time).num_days() as u32,
+            duration_days: clock.elapsed().num_days() as u32,
             threshold_reached: market.state == MarketState::Resolved,
-            statistics: market.statistics(),
+            statistics,
+            time_series: recorder.map(TimeSeriesRecorder::finish),
+            participants: participant_pnl,
+            settlement: Some(settlement),
+            fees: market.fees,
+            strategy,
+            feedback: feedback.as_ref().map(FeedbackModel::statistics),
+            final_latent_bsi: oracle.current_bsi().value(),
+            resolution_accuracy: ResolutionAccuracy::new(
+                latent_resolution_time,
+                market.resolution_time,
+            ),
+            oracle: oracle_config,
+            oracle_network: match &reporter {
+                Reporter::Single(_) => None,
+                Reporter::Network(network) => Some(network.statistics()),
+            },
         };
 
         Ok(result)
     }
 
+    /// Oracle model for a run, derived from the volatility unless overridden
+    fn oracle_config(&self) -> OracleConfig {
+        let update_frequency = self.config.update_frequency_secs;
+        match &self.config.oracle {
+            Some(oracle) => OracleConfig {
+                update_frequency,
+                ..oracle.clone()
+            },
+            None => OracleConfig {
+                update_frequency,
+                noise_level: self.config.volatility * 0.5,
+                drift_rate: self.config.volatility * 0.1,
+                mean_reversion: 0.1,
+                ..OracleConfig::default()
+            },
+        }
+    }
+
+    /// Create the virtual clock for a run, starting with the replayed series if any
+    fn create_clock(&self) -> SimClock {
+        let start = match &self.replay {
+            Some(series) => series.start(),
+            None => self.config.start_time.unwrap_or_else(default_start_time),
+        };
+        let tick = Duration::seconds(self.config.update_frequency_secs as i64);
+        let clock = SimClock::new(start, tick);
+
+        match self.config.pacing_ms {
+            Some(ms) => clock.with_pacing(std::time::Duration::from_millis(ms)),
+            None => clock,
+        }
+    }
+
+    /// Behaviors of the crowd, in equal shares or apportioned by the participant mix
+    fn crowd_behaviors(&self) -> Vec<ParticipantBehavior> {
+        let count = self.config.num_participants;
+        let Some(mix) = &self.config.participant_mix else {
+            let behaviors = ParticipantBehavior::all();
+            return (0..count).map(|i| behaviors[i % behaviors.len()]).collect();
+        };
+
+        // Largest-remainder apportionment of the participants to the weights
+        let total: f64 = mix.values().sum();
+        let mut shares: Vec<(ParticipantBehavior, usize, f64)> = mix
+            .iter()
+            .map(|(behavior, weight)| {
+                let exact = weight / total * count as f64;
+                (*behavior, exact.floor() as usize, exact.fract())
+            })
+            .collect();
+        let assigned: usize = shares.iter().map(|(_, n, _)| n).sum();
+        let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+        by_remainder.sort_by(|a, b| shares[*b].2.total_cmp(&shares[*a].2));
+        for &i in by_remainder.iter().take(count - assigned) {
+            shares[i].1 += 1;
+        }
+
+        // Interleave the behaviors through the crowd
+        let mut behaviors = Vec::with_capacity(count);
+        while behaviors.len() < count {
+            for (behavior, remaining, _) in shares.iter_mut().filter(|(_, n, _)| *n > 0) {
+                behaviors.push(*behavior);
+                *remaining -= 1;
+            }
+        }
+        behaviors
+    }
+
     /// Create participants for simulation
-    fn create_participants(&self) -> Vec<Participant> {
+    fn create_participants(&self, rng: &mut SimRng) -> Vec<Participant> {
         let mut participants = Vec::new();
-        let behaviors = ParticipantBehavior::all();
 
-        for i in 0..self.config.num_participants {
-            let behavior = behaviors[i % behaviors.len()];
-            let capital = 1000.0; // Default capital
+        for (i, behavior) in self.crowd_behaviors().into_iter().enumerate() {
+            let capital = PARTICIPANT_CAPITAL;
             let participant = Participant::new(
                 format!("participant-{}", i),
                 behavior,
                 capital,
+                rng,
             );
             participants.push(participant);
         }
@@ -133,76 +624,317 @@ impl Simulator {
         participants
     }
 
-    /// Create a trade for a participant
-    fn create_trade(
+    /// Carry out an agent's action on the configured venue
+    ///
+    /// Orders are capped at the size the participant can pay for. Instant
+    /// fills are priced by the pricing mechanism, including its price impact,
+    /// and a limit the current price violates is not filled. On an order book
+    /// each participant keeps at most one live order, paid for at the limit
+    /// price, or at 1.0 per contract for market orders.
+    fn execute_action(
         &self,
-        participant: &Participant,
-        current_bsi: BSI,
+        index: usize,
+        action: AgentAction,
+        accounts: &mut Accounts,
+        market: &mut Market,
         timestamp: DateTime<Utc>,
         counter: &mut usize,
-    ) -> Trade {
-        *counter += 1;
-        let _position_type = participant.determine_position_type(current_bsi, self.config.threshold);
-        let size = participant.calculate_position_size();
+    ) -> Result<Vec<Trade>> {
+        let (side, size, limit_price) = match action {
+            AgentAction::Hold => return Ok(Vec::new()),
+            AgentAction::Market { side, size } => (side, size, None),
+            AgentAction::Limit { side, size, price } => (side, size, Some(price)),
+        };
+        if !(size.is_finite() && size > f64::EPSILON) {
+            return Ok(Vec::new());
+        }
 
-        Trade {
-            id: format!("trade-{}", counter),
-            participant_id: participant.id.clone(),
-            trade_type: TradeType::Open,
-            size,
-            price: current_bsi.value(),
-            timestamp,
-            bsi_at_trade: current_bsi,
+        let participant = &mut accounts.participants[index];
+        match self.config.venue {
+            VenueConfig::Instant => {
+                let price = market.market_price();
+                let limit_violated = limit_price.is_some_and(|limit| match side {
+                    PositionType::Long => price > limit,
+                    PositionType::Short => price < limit,
+                });
+                if limit_violated {
+                    return Ok(Vec::new());
+                }
+
+                // Instant fills pay the taker fee on top of the price
+                let fee_rate = market.fee_schedule.trade_fee(1.0, None);
+                let unit_cost = side.unit_price(price) * (1.0 + fee_rate);
+                let mut size = participant.affordable_size(side, size, unit_cost);
+                // Price impact makes the opening leg dearer than the current
+                // price; the cost is convex, so scaling it down proportionally
+                // keeps it within capital
+                let reducible = participant
+                    .position()
+                    .filter(|position| position.position_type != side)
+                    .map_or(0.0, |position| position.size);
+                let opening = size - reducible;
+                if opening > f64::EPSILON {
+                    let cost = market.quote(side, opening)? * (1.0 + fee_rate);
+                    let capital = participant.capital.max(0.0);
+                    if cost > capital {
+                        size = reducible + opening * capital / cost;
+                    }
+                }
+                if size <= f64::EPSILON {
+                    return Ok(Vec::new());
+                }
+
+                let trades =
+                    self.execute_order(participant, side, size, market, timestamp, counter)?;
+                market.set_position(&participant.id, participant.position().cloned());
+                Ok(trades)
+            }
+            VenueConfig::OrderBook(_) => {
+                // Reducing an opposite position pays out; only the opening leg
+                // needs capital. A limit order may rest and fill as a maker, so
+                // it reserves the higher of the two fees.
+                let taker_rate = market.fee_schedule.trade_fee(1.0, Some(LiquidityRole::Taker));
+                let maker_rate = market.fee_schedule.trade_fee(1.0, Some(LiquidityRole::Maker));
+                let (price, fee_rate) = match limit_price {
+                    Some(price) => (side.unit_price(price), taker_rate.max(maker_rate)),
+                    None => (1.0, taker_rate),
+                };
+                let unit_cost = price * (1.0 + fee_rate);
+                let size = participant.affordable_size(side, size, unit_cost);
+                if size <= f64::EPSILON {
+                    return Ok(Vec::new());
+                }
+
+                let participant_id = participant.id.clone();
+                market.cancel_orders(&participant_id);
+                let side = OrderSide::for_position(side);
+                let result =
+                    market.submit_order(&participant_id, side, limit_price, size, timestamp)?;
+                Ok(self.fill_executions(&result.executions, accounts, market, timestamp, counter))
+            }
         }
     }
 
-    /// Determine if shock should be applied based on scenario
-    fn should_apply_shock(
+    /// Fill `size` contracts of `side` instantly against the pricing mechanism
+    ///
+    /// An order against an opposite-side position is split into a leg that
+    /// sells held contracts back and a leg that buys the remainder.
+    fn execute_order(
         &self,
-        scenario: &Scenario,
-        current_time: DateTime<Utc>,
-        start_time: DateTime<Utc>,
-    ) -> Option<f64> {
-        let elapsed_days = (current_time - start_time).num_days();
-        let mut rng = rand::thread_rng();
+        participant: &mut Participant,
+        side: PositionType,
+        size: f64,
+        market: &mut Market,
+        timestamp: DateTime<Utc>,
+        counter: &mut usize,
+    ) -> Result<Vec<Trade>> {
+        let current_bsi = market.current_bsi;
+        let reduce = participant
+            .position()
+            .filter(|position| position.position_type != side)
+            .map_or(0.0, |position| position.size.min(size));
+        let legs = [(side.opposite(), -reduce), (side, size - reduce)];
 
-        match scenario {
-            Scenario::FlashCrash if elapsed_days == 7 => Some(-0.3),
-            Scenario::SentimentReversal if elapsed_days == 10 => Some(0.4),
-            Scenario::HighVolatility if rng.gen_bool(0.1) => {
-                Some(rng.gen_range(-0.2..0.2))
+        let mut trades = Vec::new();
+        for (leg_side, leg_size) in legs {
+            if leg_size.abs() <= f64::EPSILON {
+                continue;
+            }
+
+            let fill = market.execute(leg_side, leg_size)?;
+            for change in participant.apply_fill(side, leg_size.abs(), fill.average_price, timestamp) {
+                let notional = change.size * change.position_type.unit_price(fill.average_price);
+                let fee = market.fee_schedule.trade_fee(notional, None);
+                participant.pay_fee(fee);
+                *counter += 1;
+                trades.push(Trade {
+                    id: format!("trade-{}", counter),
+                    participant_id: participant.id.clone(),
+                    trade_type: change.trade_type,
+                    position_type: change.position_type,
+                    size: change.size,
+                    price: fill.average_price,
+                    timestamp,
+                    bsi_at_trade: current_bsi,
+                    realized_pnl: change.realized_pnl,
+                    slippage: fill.slippage,
+                    fee,
+                    order_id: None,
+                    liquidity_role: None,
+                    partial_fill: false,
+                });
             }
-            _ => None,
         }
+
+        Ok(trades)
+    }
+
+    /// Apply order book matches to both the maker and the taker
+    fn fill_executions(
+        &self,
+        executions: &[Execution],
+        accounts: &mut Accounts,
+        market: &mut Market,
+        timestamp: DateTime<Utc>,
+        counter: &mut usize,
+    ) -> Vec<Trade> {
+        let mut trades = Vec::new();
+        let best_price = executions.first().map(|e| e.price);
+
+        for execution in executions {
+            let taker_side = execution.taker_side.position_type();
+            let taker_slippage =
+                best_price.map_or(0.0, |best| execution.size * (execution.price - best).abs());
+            let legs = [
+                (
+                    &execution.maker_participant_id,
+                    taker_side.opposite(),
+                    execution.maker_order_id,
+                    LiquidityRole::Maker,
+                    execution.maker_partial,
+                    0.0,
+                ),
+                (
+                    &execution.taker_participant_id,
+                    taker_side,
+                    execution.taker_order_id,
+                    LiquidityRole::Taker,
+                    execution.taker_partial,
+                    taker_slippage,
+                ),
+            ];
+
+            for (participant_id, side, order_id, role, partial, slippage) in legs {
+                let participant = &mut accounts.participants[accounts.index[participant_id]];
+                let changes =
+                    participant.apply_fill(side, execution.size, execution.price, timestamp);
+                for change in changes {
+                    let notional = change.size * change.position_type.unit_price(execution.price);
+                    let fee = market.fee_schedule.trade_fee(notional, Some(role));
+                    participant.pay_fee(fee);
+                    *counter += 1;
+                    trades.push(Trade {
+                        id: format!("trade-{}", counter),
+                        participant_id: participant.id.clone(),
+                        trade_type: change.trade_type,
+                        position_type: change.position_type,
+                        size: change.size,
+                        price: execution.price,
+                        timestamp,
+                        bsi_at_trade: market.current_bsi,
+                        realized_pnl: change.realized_pnl,
+                        slippage,
+                        fee,
+                        order_id: Some(order_id),
+                        liquidity_role: Some(role),
+                        partial_fill: partial,
+                    });
+                }
+                market.set_position(participant_id, participant.position().cloned());
+            }
+        }
+
+        trades
     }
 }
 
 /// Result of a simulation run
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SimulationResult {
     /// Market ID
     pub market_id: String,
+    /// Master seed the run was executed with
+    pub seed: u64,
     /// Scenario used
     pub scenario: Scenario,
+    /// Name of the scenario timeline run
+    pub scenario_name: String,
+    /// Name of the resolution rule applied
+    pub resolution_rule: String,
     /// Final BSI value
     pub final_bsi: f64,
     /// Total trading volume
     pub total_volume: f64,
     /// Total number of trades
     pub total_trades: usize,
-    /// Resolution time (if resolved)
+    /// First time the BSI reached the threshold
+    pub first_crossing_time: Option<DateTime<Utc>>,
+    /// Confirmed resolution time (if resolved)
     pub resolution_time: Option<DateTime<Utc>>,
     /// Actual duration in days
     pub duration_days: u32,
     /// Whether threshold was reached
     pub threshold_reached: bool,
-    /// Market statistics
+    /// Market statistics at close, before settlement
     pub statistics: crate::market::MarketStatistics,
+    /// Per-tick time series (if recording was enabled)
+    pub time_series: Option<TimeSeries>,
+    /// Capital and profit and loss per participant at close, before settlement
+    pub participants: Vec<ParticipantPnl>,
+    /// Payouts made when the market resolved or expired
+    pub settlement: Option<SettlementReport>,
+    /// Trading and settlement fees collected
+    pub fees: FeeLedger,
+    /// Performance of the strategy under test (if one was added)
+    pub strategy: Option<StrategyBacktest>,
+    /// Feedback applied to the BSI (if the feedback model was enabled)
+    pub feedback: Option<FeedbackStatistics>,
+    /// Latent belief at the end of the run
+    pub final_latent_bsi: f64,
+    /// Market resolution compared with the latent belief
+    pub resolution_accuracy: ResolutionAccuracy,
+    /// Oracle model the run was executed with
+    pub oracle: OracleConfig,
+    /// Reporting of the oracle network (if one published the BSI)
+    pub oracle_network: Option<OracleNetworkStatistics>,
+}
+
+/// Profit and loss summary for one participant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantPnl {
+    /// Participant ID
+    pub participant_id: String,
+    /// Name of the agent trading the account
+    pub agent: String,
+    /// Capital at the end of the run
+    pub capital: f64,
+    /// Profit and loss realized from closed contracts
+    pub realized_pnl: f64,
+    /// Unrealized profit and loss marked at the final market price
+    pub unrealized_pnl: f64,
+    /// Trading fees paid
+    pub fees_paid: f64,
+}
+
+impl ParticipantPnl {
+    /// Summarize a participant traded by `agent`, marking open positions at `price`
+    pub fn new(participant: &Participant, agent: String, price: f64) -> Self {
+        ParticipantPnl {
+            participant_id: participant.id.clone(),
+            agent,
+            capital: participant.capital,
+            realized_pnl: participant.realized_pnl,
+            unrealized_pnl: participant.unrealized_pnl(price),
+            fees_paid: participant.fees_paid,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::feedback::{FeedbackConfig, FeedbackSource};
+    use crate::analytics::Analytics;
+    use crate::fees::FeeConfig;
+    use crate::network::{NodeBehavior, OracleNetworkConfig};
+    use crate::orderbook::OrderBookConfig;
+    use crate::pricing::PricingConfig;
+    use crate::resolution::ResolutionRuleConfig;
+    use crate::scenario::Trigger;
+    use crate::settlement::SettlementOutcome;
+    use crate::strategy::Strategy;
+    use crate::timeseries::TimeSeriesConfig;
+    use chrono::TimeZone;
 
     #[tokio::test]
     async fn test_simulator_run() {
@@ -222,4 +954,658 @@ mod tests {
         let result = result.unwrap();
         assert!(result.final_bsi >= 0.0 && result.final_bsi <= 1.0);
     }
+
+    #[tokio::test]
+    async fn test_seeded_runs_are_identical() {
+        let config = SimulationConfig::builder()
+            .duration_days(5)
+            .num_participants(50)
+            .volatility(0.3)
+            .seed(1234)
+            .build()
+            .unwrap();
+
+        let simulator = Simulator::new(config);
+        let a = simulator.run(Scenario::HighVolatility).await.unwrap();
+        let b = simulator.run(Scenario::HighVolatility).await.unwrap();
+
+        assert_eq!(a.seed, 1234);
+        assert_eq!(a, b);
+    }
+
+    #[tokio::test]
+    async fn test_time_series_capture() {
+        let config = SimulationConfig::builder()
+            .duration_days(1)
+            .num_participants(20)
+            .threshold(1.0)
+            .update_frequency_secs(300)
+            .time_series(TimeSeriesConfig {
+                sample_every: 12,
+                max_points: None,
+            })
+            .seed(3)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::Sideways).await.unwrap();
+        let series = result.time_series.unwrap();
+
+        // 288 five-minute ticks in a day, one point per hour
+        assert_eq!(series.points.len(), 24);
+        assert_eq!(series.points.iter().map(|p| p.trades).sum::<usize>(), result.total_trades);
+        assert_eq!(series.points.last().unwrap().bsi, result.final_bsi);
+    }
+
+    #[tokio::test]
+    async fn test_positions_track_trades() {
+        let config = SimulationConfig::builder()
+            .duration_days(3)
+            .num_participants(30)
+            .threshold(1.0)
+            .seed(11)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::Sideways).await.unwrap();
+        let stats = &result.statistics;
+
+        assert!(stats.active_positions > 0);
+        assert_eq!(result.participants.len(), 30);
+
+        // Market PnL is the sum of participant PnL
+        let realized: f64 = result.participants.iter().map(|p| p.realized_pnl).sum();
+        let unrealized: f64 = result.participants.iter().map(|p| p.unrealized_pnl).sum();
+        assert!((stats.realized_pnl - realized).abs() < 1e-6);
+        assert!((stats.unrealized_pnl - unrealized).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_run_settles_market() {
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(30)
+            .threshold(1.0)
+            .seed(21)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::Sideways).await.unwrap();
+        let settlement = result.settlement.unwrap();
+
+        assert!(!result.threshold_reached);
+        assert_eq!(settlement.outcome, SettlementOutcome::Expired);
+        assert_eq!(settlement.payouts.len(), result.statistics.active_positions);
+        assert!(settlement.is_conserved());
+    }
+
+    #[tokio::test]
+    async fn test_amm_pricing_has_price_impact() {
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(30)
+            .threshold(1.0)
+            .pricing(PricingConfig::Lmsr { liquidity: 500.0 })
+            .seed(8)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::BullishTrend).await.unwrap();
+        let stats = &result.statistics;
+        let settlement = result.settlement.unwrap();
+
+        assert!(stats.total_slippage > 0.0);
+        assert_ne!(stats.market_price, stats.current_bsi);
+        // The LMSR market maker never loses more than its bound
+        assert!(settlement.house_pnl >= -stats.lp_worst_case_loss.unwrap() - 1e-6);
+        assert!(settlement.is_conserved());
+    }
+
+    #[tokio::test]
+    async fn test_order_book_venue() {
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(40)
+            .threshold(1.0)
+            .venue(VenueConfig::OrderBook(OrderBookConfig::default()))
+            .seed(13)
+            .build()
+            .unwrap();
+
+        let simulator = Simulator::new(config);
+        let result = simulator.run(Scenario::HighVolatility).await.unwrap();
+        let book = result.statistics.order_book.clone().unwrap();
+        let settlement = result.settlement.unwrap();
+
+        assert!(book.executions > 0);
+        assert!(book.avg_spread.is_some_and(|spread| spread > 0.0));
+        assert!(book.avg_queue_length > 0.0);
+        assert_eq!(result.statistics.active_positions, settlement.payouts.len());
+        // Peer-to-peer fills leave nothing for the house
+        assert!(settlement.house_pnl.abs() < 1e-6);
+        assert!(settlement.is_conserved());
+    }
+
+    #[tokio::test]
+    async fn test_fees_are_collected_and_conserved() {
+        let fees = FeeConfig {
+            taker_fee_bps: 30,
+            maker_fee_bps: 10,
+            settlement_fee_bps: 100,
+            protocol_share: 0.4,
+        };
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(30)
+            .threshold(1.0)
+            .venue(VenueConfig::OrderBook(OrderBookConfig::default()))
+            .fees(fees)
+            .seed(17)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::Sideways).await.unwrap();
+        let settlement = result.settlement.unwrap();
+        let paid: f64 = result.participants.iter().map(|p| p.fees_paid).sum();
+
+        assert!(result.fees.trading_fees > 0.0);
+        assert!(result.fees.settlement_fees > 0.0);
+        assert!((result.fees.trading_fees - paid).abs() < 1e-6);
+        assert!((result.fees.treasury - 0.4 * result.fees.total()).abs() < 1e-6);
+        assert!((settlement.fees - result.fees.total()).abs() < 1e-9);
+        assert!(settlement.is_conserved());
+    }
+
+    #[tokio::test]
+    async fn test_strategy_trades_in_simulation() {
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(20)
+            .threshold(1.0)
+            .pricing(PricingConfig::Lmsr { liquidity: 2000.0 })
+            .seed(31)
+            .build()
+            .unwrap();
+        let strategy = Strategy::MeanReversion {
+            mean: 0.5,
+            deviation: 0.05,
+        };
+
+        let simulator = Simulator::new(config)
+            .with_strategy(strategy.build(), PositionSizing::default());
+        let result = simulator.run(Scenario::Sideways).await.unwrap();
+        let report = result.strategy.clone().unwrap();
+        let settlement = result.settlement.unwrap();
+
+        assert_eq!(report.strategy_name, "Mean Reversion");
+        assert!(report.num_trades > 0);
+        assert!(report.trades.iter().all(|t| t.participant_id == STRATEGY_PARTICIPANT_ID));
+        // One point per tick plus the settlement point
+        assert_eq!(report.equity_curve.len(), 2 * 288 + 1);
+        // The strategy is one agent among the crowd
+        assert_eq!(result.participants.len(), 21);
+        assert_eq!(result.participants[20].agent, "Mean Reversion");
+        assert!(settlement.is_conserved());
+    }
+
+    /// Agent that posts one resting bid and then holds
+    #[derive(Debug, Clone, Default)]
+    struct RestingBid {
+        filled: f64,
+        settled: bool,
+    }
+
+    impl Agent for RestingBid {
+        fn name(&self) -> String {
+            "Resting Bid".to_string()
+        }
+
+        fn decide(&mut self, observation: &AgentObservation<'_>, _rng: &mut SimRng) -> AgentAction {
+            if self.filled > 0.0 || observation.participant.position().is_some() {
+                return AgentAction::Hold;
+            }
+            AgentAction::Limit {
+                side: PositionType::Long,
+                size: 50.0,
+                price: 0.45,
+            }
+        }
+
+        fn on_fill(&mut self, trade: &Trade) {
+            assert_eq!(trade.participant_id, "agent-0");
+            self.filled += trade.size;
+        }
+
+        fn on_settlement(&mut self, _outcome: SettlementOutcome, _pnl: f64) {
+            self.settled = true;
+        }
+
+        fn clone_box(&self) -> Box<dyn Agent> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_agent_trades_among_crowd() {
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(30)
+            .threshold(1.0)
+            .venue(VenueConfig::OrderBook(OrderBookConfig::default()))
+            .seed(19)
+            .build()
+            .unwrap();
+
+        let simulator = Simulator::new(config).with_agent(Box::new(RestingBid::default()));
+        let result = simulator.run(Scenario::Sideways).await.unwrap();
+        let agent = result.participants.last().unwrap();
+
+        assert_eq!(result.participants.len(), 31);
+        assert_eq!(agent.participant_id, "agent-0");
+        assert_eq!(agent.agent, "Resting Bid");
+        // The bid was filled, paying for the contracts out of capital
+        assert!(agent.capital < PARTICIPANT_CAPITAL);
+        assert!(result.participants[..30].iter().any(|p| p.agent == "Momentum"));
+        assert!(result.settlement.unwrap().is_conserved());
+    }
+
+    /// Agent that buys one YES contract every tick
+    #[derive(Debug, Clone)]
+    struct YesBuyer;
+
+    impl Agent for YesBuyer {
+        fn name(&self) -> String {
+            "YES Buyer".to_string()
+        }
+
+        fn decide(&mut self, _: &AgentObservation<'_>, _rng: &mut SimRng) -> AgentAction {
+            AgentAction::Market {
+                side: PositionType::Long,
+                size: 1.0,
+            }
+        }
+
+        fn clone_box(&self) -> Box<dyn Agent> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// Agent that orders far more contracts than it can pay for
+    #[derive(Debug, Clone)]
+    struct Overspender {
+        side: PositionType,
+        price: Option<f64>,
+    }
+
+    impl Agent for Overspender {
+        fn name(&self) -> String {
+            "Overspender".to_string()
+        }
+
+        fn decide(&mut self, _: &AgentObservation<'_>, _rng: &mut SimRng) -> AgentAction {
+            match self.price {
+                Some(price) => AgentAction::Limit {
+                    side: self.side,
+                    size: 1e6,
+                    price,
+                },
+                None => AgentAction::Market {
+                    side: self.side,
+                    size: 1e6,
+                },
+            }
+        }
+
+        fn clone_box(&self) -> Box<dyn Agent> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instant_orders_are_capped_at_capital() {
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(5)
+            .threshold(1.0)
+            .seed(29)
+            .build()
+            .unwrap();
+        let run = |agent: Overspender, pricing: Option<PricingConfig>| {
+            let mut config = config.clone();
+            if let Some(pricing) = pricing {
+                config.pricing = pricing;
+            }
+            async move {
+                let result = Simulator::new(config)
+                    .with_agent(Box::new(agent))
+                    .run(Scenario::Sideways)
+                    .await
+                    .unwrap();
+                result.participants.last().unwrap().clone()
+            }
+        };
+
+        let market = Overspender {
+            side: PositionType::Long,
+            price: None,
+        };
+        for pricing in [None, Some(PricingConfig::Lmsr { liquidity: 1000.0 })] {
+            let agent = run(market.clone(), pricing).await;
+            assert!(agent.capital >= 0.0, "capital {}", agent.capital);
+            assert!(agent.capital < PARTICIPANT_CAPITAL);
+        }
+
+        // A YES price never reaches 0.0, so the limit never fills
+        let limit = Overspender {
+            side: PositionType::Long,
+            price: Some(0.0),
+        };
+        assert_eq!(run(limit, None).await.capital, PARTICIPANT_CAPITAL);
+    }
+
+    #[tokio::test]
+    async fn test_order_caps_include_fees() {
+        let fees = FeeConfig {
+            taker_fee_bps: 1000,
+            maker_fee_bps: 500,
+            ..FeeConfig::default()
+        };
+        let base = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(5)
+            .threshold(1.0)
+            .fees(fees)
+            .seed(29)
+            .build()
+            .unwrap();
+        let book = SimulationConfig {
+            venue: VenueConfig::OrderBook(OrderBookConfig::default()),
+            ..base.clone()
+        };
+        let lmsr = SimulationConfig {
+            pricing: PricingConfig::Lmsr { liquidity: 1000.0 },
+            ..base.clone()
+        };
+        let market = Overspender {
+            side: PositionType::Long,
+            price: None,
+        };
+        let resting = Overspender {
+            side: PositionType::Long,
+            price: Some(0.5),
+        };
+
+        for (config, agent) in [
+            (base, market.clone()),
+            (lmsr, market.clone()),
+            (book.clone(), market),
+            (book, resting),
+        ] {
+            let result = Simulator::new(config)
+                .with_agent(Box::new(agent))
+                .run(Scenario::Sideways)
+                .await
+                .unwrap();
+            let agent = result.participants.last().unwrap();
+            assert!(agent.fees_paid > 0.0);
+            assert!(agent.capital >= -1e-9, "capital {}", agent.capital);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_order_flow_feedback_drives_crossing() {
+        let base = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(1)
+            .threshold(0.9)
+            .resolution_rule(ResolutionRuleConfig::FirstCrossing)
+            .seed(23);
+        let feedback = FeedbackConfig {
+            weight: 0.05,
+            lag_ticks: 3,
+            source: FeedbackSource::OrderFlow,
+        };
+
+        let run = |config: SimulationConfig| async move {
+            Simulator::new(config)
+                .with_agent(Box::new(YesBuyer))
+                .run(Scenario::Sideways)
+                .await
+                .unwrap()
+        };
+        let exogenous = run(base.clone().build().unwrap()).await;
+        let reflexive = run(base.feedback(feedback).build().unwrap()).await;
+        let statistics = reflexive.feedback.unwrap();
+
+        // Persistent YES buying talks the belief over the threshold
+        assert!(exogenous.feedback.is_none());
+        assert!(!exogenous.threshold_reached);
+        assert!(reflexive.threshold_reached);
+        assert!(statistics.net_adjustment > 0.0);
+        assert!(statistics.max_adjustment <= feedback.weight);
+    }
+
+    #[tokio::test]
+    async fn test_delayed_oracle_detects_late() {
+        let oracle = OracleConfig {
+            noise_level: 0.02,
+            drift_rate: 0.2,
+            mean_reversion: 0.0,
+            observation_delay: 12,
+            ..OracleConfig::default()
+        };
+        let config = SimulationConfig::builder()
+            .duration_days(5)
+            .num_participants(10)
+            .threshold(0.7)
+            .resolution_rule(ResolutionRuleConfig::FirstCrossing)
+            .oracle(oracle)
+            .seed(29)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::BullishTrend).await.unwrap();
+        let accuracy = result.resolution_accuracy;
+
+        // Observations trail the belief by exactly twelve five-minute updates
+        assert!(result.threshold_reached);
+        assert!(!accuracy.false_positive && !accuracy.false_negative);
+        assert_eq!(accuracy.detection_delay_secs, Some(12 * 300));
+        assert_eq!(result.oracle.observation_delay, 12);
+    }
+
+    #[tokio::test]
+    async fn test_custom_timeline_pauses_and_adds_participants() {
+        let definition = ScenarioDefinition::new("Rally with halt")
+            .event(Trigger::Start, ScenarioAction::SetTarget { target: 0.9 })
+            .event(
+                Trigger::After { hours: 2.0 },
+                ScenarioAction::ParticipantInflux {
+                    count: 5,
+                    behavior: Some(ParticipantBehavior::Aggressive),
+                },
+            )
+            .event(
+                Trigger::BsiAbove { level: 0.6 },
+                ScenarioAction::PauseTrading { hours: 6.0 },
+            );
+        let json = definition.to_json().unwrap();
+        let definition = ScenarioDefinition::from_json(&json).unwrap();
+        let config = SimulationConfig::builder()
+            .duration_days(3)
+            .num_participants(10)
+            .threshold(0.95)
+            .time_series(TimeSeriesConfig::default())
+            .seed(17)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run_custom(&definition).await.unwrap();
+
+        assert_eq!(result.scenario, Scenario::Custom);
+        assert_eq!(result.scenario_name, "Rally with halt");
+        assert_eq!(result.participants.len(), 15);
+        assert_eq!(result.participants[14].participant_id, "influx-4");
+        assert_eq!(result.participants[14].agent, "Aggressive");
+
+        // Six hours of five-minute updates pass without trading
+        let points = &result.time_series.as_ref().unwrap().points;
+        let paused: Vec<_> = points
+            .iter()
+            .filter(|p| p.state == MarketState::Paused)
+            .collect();
+        assert_eq!(paused.len(), 72);
+        assert!(paused.iter().all(|p| p.trades == 0));
+        assert!(points.last().unwrap().latent_bsi > 0.6);
+    }
+
+    #[tokio::test]
+    async fn test_pause_past_end_lasts_until_end() {
+        let definition = ScenarioDefinition::new("Frozen").event(
+            Trigger::Start,
+            ScenarioAction::PauseTrading { hours: 1e6 },
+        );
+        let config = SimulationConfig::builder()
+            .duration_days(1)
+            .num_participants(10)
+            .seed(17)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run_custom(&definition).await.unwrap();
+
+        assert_eq!(result.total_trades, 0);
+        assert!(!result.threshold_reached);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_series_drives_market() {
+        // Hourly history rising from 0.5 to 0.9 over two days; the simulation ticks every 5 minutes
+        let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+        let csv: String = std::iter::once("timestamp,value\n".to_string())
+            .chain((0..=48).map(|h| {
+                let time = start + Duration::hours(h);
+                format!("{},{}\n", time.to_rfc3339(), 0.5 + 0.4 * h as f64 / 48.0)
+            }))
+            .collect();
+        let series = BsiSeries::from_csv_str(&csv).unwrap();
+        let config = SimulationConfig::builder()
+            .duration_days(3)
+            .num_participants(10)
+            .threshold(0.75)
+            .resolution_rule(ResolutionRuleConfig::FirstCrossing)
+            .time_series(TimeSeriesConfig::default())
+            .seed(3)
+            .build()
+            .unwrap();
+
+        let simulator = Simulator::new(config).with_replay(series);
+        // Replays ignore scenario targets, so a bearish run follows the history
+        let result = simulator.run(Scenario::BearishTrend).await.unwrap();
+
+        let first = &result.time_series.as_ref().unwrap().points[0];
+        assert_eq!(first.timestamp, start);
+        assert_eq!(first.latent_bsi, 0.5);
+        assert!(result.threshold_reached);
+        assert_eq!(result.resolution_time, Some(start + Duration::hours(30)));
+    }
+
+    #[tokio::test]
+    async fn test_biased_oracle_resolves_falsely() {
+        let oracle = OracleConfig {
+            noise_level: 0.01,
+            drift_rate: 0.01,
+            observation_bias: 0.3,
+            ..OracleConfig::default()
+        };
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(10)
+            .threshold(0.75)
+            .resolution_rule(ResolutionRuleConfig::FirstCrossing)
+            .oracle(oracle)
+            .time_series(TimeSeriesConfig::default())
+            .seed(37)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::Sideways).await.unwrap();
+        let series = result.time_series.unwrap();
+
+        // The belief stays near 0.5 but the oracle reports it 0.3 higher
+        assert!(result.threshold_reached);
+        assert!(result.resolution_accuracy.false_positive);
+        assert!(series.points.iter().all(|p| p.latent_bsi < 0.75));
+        assert!(result.final_latent_bsi < result.final_bsi);
+    }
+
+    #[tokio::test]
+    async fn test_colluding_majority_forces_wrong_resolution() {
+        let colluding = NodeBehavior::Colluding { target: 1.0 };
+        let mut results = Vec::new();
+        for faulty in 0..=4 {
+            let config = SimulationConfig::builder()
+                .duration_days(1)
+                .num_participants(10)
+                .threshold(0.8)
+                .resolution_rule(ResolutionRuleConfig::FirstCrossing)
+                .oracle_network(OracleNetworkConfig::honest(7).with_adversaries(faulty, colluding))
+                .seed(41)
+                .build()
+                .unwrap();
+            results.push(Simulator::new(config).run(Scenario::Sideways).await.unwrap());
+        }
+
+        let resistance = Analytics::manipulation_resistance(&results, 1.0);
+
+        // The median holds until colluders are a majority of the seven nodes
+        assert_eq!(resistance.nodes_to_force_wrong_resolution, Some(4));
+        assert_eq!(resistance.by_faulty_nodes.len(), 5);
+        assert!(results[4].resolution_accuracy.false_positive);
+        assert!(results[0].oracle_network.unwrap().avg_reports > 6.0);
+    }
+
+    #[tokio::test]
+    async fn test_alternative_rules_share_bsi_path() {
+        let base = SimulationConfig::builder()
+            .duration_days(10)
+            .num_participants(10)
+            .volatility(0.4)
+            .threshold(0.7)
+            .seed(77);
+        let first = base.clone().resolution_rule(ResolutionRuleConfig::FirstCrossing);
+        let twap = base.resolution_rule(ResolutionRuleConfig::TimeWeightedAverage { window_hours: 12 });
+
+        let a = Simulator::new(first.build().unwrap()).run(Scenario::BullishTrend).await.unwrap();
+        let b = Simulator::new(twap.build().unwrap()).run(Scenario::BullishTrend).await.unwrap();
+
+        assert_eq!(a.resolution_rule, "first_crossing");
+        assert_eq!(b.resolution_rule, "time_weighted_average");
+        // The averaged rule can never resolve before the first crossing on the same path
+        let first_time = a.resolution_time.unwrap();
+        let twap_time = b.resolution_time.unwrap();
+        assert!(first_time < twap_time);
+    }
+
+    #[tokio::test]
+    async fn test_run_anchored_at_start_time() {
+        let start = Utc.with_ymd_and_hms(2021, 11, 3, 12, 0, 0).unwrap();
+        let config = SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(10)
+            .threshold(1.0)
+            .start_time(start)
+            .time_series(TimeSeriesConfig::default())
+            .seed(5)
+            .build()
+            .unwrap();
+
+        let result = Simulator::new(config).run(Scenario::Sideways).await.unwrap();
+
+        assert_eq!(result.market_id, format!("sim-{}-{:016x}", start.timestamp(), 5));
+        assert_eq!(result.time_series.unwrap().points[0].timestamp, start);
+        // An unreachable threshold expires the market at the end of the interval
+        assert_eq!(result.resolution_time, None);
+        assert_eq!(result.settlement.unwrap().settled_at, start + Duration::days(2));
+    }
 }
@@ -1,7 +1,17 @@
 //! Trading strategy and backtesting
 
-use crate::types::BSI;
+use crate::clock::default_start_time;
+use crate::error::{Result, SimulatorError};
+use crate::fees::FeeConfig;
+use crate::settlement::SettlementOutcome;
+use crate::types::{PositionType, Trade, TradeType, BSI};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// Smallest contract price a target position is sized against
+const MIN_UNIT_PRICE: f64 = 1e-6;
 
 /// Trading strategy
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,42 +31,18 @@ pub enum Strategy {
 impl Strategy {
     /// Evaluate strategy signal (-1.0 to 1.0)
     /// Negative = short signal, Positive = long signal
+    ///
+    /// Replays `history` through a fresh [`TradingStrategy`] built from this
+    /// variant, then reports its target for `current_bsi`.
     pub fn evaluate(&self, current_bsi: BSI, history: &[BSI]) -> f64 {
-        match self {
-            Strategy::ThresholdCrossing { threshold } => {
-                if current_bsi.value() < *threshold {
-                    1.0 // Long signal
-                } else {
-                    -1.0 // Short signal
-                }
-            }
-            Strategy::Momentum { lookback_periods } => {
-                if history.len() < *lookback_periods {
-                    return 0.0;
-                }
-
-                let start_idx = history.len() - lookback_periods;
-                let start_bsi = history[start_idx].value();
-                let momentum = current_bsi.value() - start_bsi;
+        let mut strategy = self.build();
+        for &bsi in history {
+            strategy.on_tick(&MarketObservation::from_bsi(bsi));
+        }
 
-                momentum.clamp(-1.0, 1.0)
-            }
-            Strategy::MeanReversion { mean, deviation } => {
-                let distance = current_bsi.value() - mean;
-                if distance.abs() > *deviation {
-                    -distance.signum() // Revert to mean
-                } else {
-                    0.0
-                }
-            }
-            Strategy::Contrarian { threshold } => {
-                if current_bsi.value() > *threshold {
-                    -1.0 // Short when above threshold
-                } else {
-                    1.0 // Long when below threshold
-                }
-            }
-            Strategy::Custom { .. } => 0.0,
+        match strategy.on_tick(&MarketObservation::from_bsi(current_bsi)) {
+            StrategyAction::Target(signal) => signal,
+            StrategyAction::Hold | StrategyAction::Order { .. } => 0.0,
         }
     }
 
@@ -70,15 +56,324 @@ impl Strategy {
             Strategy::Custom { name } => name.clone(),
         }
     }
+
+    /// Build the strategy as a stateful [`TradingStrategy`]
+    pub fn build(&self) -> Box<dyn TradingStrategy> {
+        match self {
+            Strategy::ThresholdCrossing { threshold } => {
+                Box::new(ThresholdCrossing { threshold: *threshold })
+            }
+            Strategy::Momentum { lookback_periods } => {
+                Box::new(Momentum::new(*lookback_periods))
+            }
+            Strategy::MeanReversion { mean, deviation } => Box::new(MeanReversion {
+                mean: *mean,
+                deviation: *deviation,
+            }),
+            Strategy::Contrarian { threshold } => Box::new(Contrarian { threshold: *threshold }),
+            Strategy::Custom { name } => Box::new(Passive { name: name.clone() }),
+        }
+    }
+}
+
+/// Market state visible to a strategy on each update
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketObservation {
+    /// Update time
+    pub timestamp: DateTime<Utc>,
+    /// Oracle BSI
+    pub bsi: BSI,
+    /// Market YES price
+    pub market_price: f64,
+    /// Net position in contracts (positive = long, negative = short)
+    pub position: f64,
+    /// Free capital
+    pub capital: f64,
+    /// Capital plus open position marked at the market price
+    pub equity: f64,
+}
+
+impl MarketObservation {
+    /// Observation of a bare BSI value with no position, priced at the BSI
+    pub fn from_bsi(bsi: BSI) -> Self {
+        MarketObservation {
+            timestamp: default_start_time(),
+            bsi,
+            market_price: bsi.value(),
+            position: 0.0,
+            capital: 0.0,
+            equity: 0.0,
+        }
+    }
+}
+
+/// What a strategy wants to do after an update
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrategyAction {
+    /// Keep the current position
+    Hold,
+    /// Move to a target exposure in `[-1, 1]` of the maximum position (negative = short)
+    Target(f64),
+    /// Buy `size` contracts of `side`, netting against an opposite position first
+    Order { side: PositionType, size: f64 },
+}
+
+/// A trading strategy driven by market updates
+///
+/// Strategies are stateful: they see every update in time order and are told
+/// about their fills and the final settlement.
+pub trait TradingStrategy: Debug + Send + Sync {
+    /// Strategy name used in reports
+    fn name(&self) -> String;
+
+    /// Observe an update and decide what to do
+    fn on_tick(&mut self, observation: &MarketObservation) -> StrategyAction;
+
+    /// Receive a trade executed on the strategy's behalf
+    fn on_fill(&mut self, _trade: &Trade) {}
+
+    /// Receive the market outcome and the profit and loss of the settled position
+    fn on_settlement(&mut self, _outcome: SettlementOutcome, _pnl: f64) {}
+
+    /// Clone into a boxed trait object
+    fn clone_box(&self) -> Box<dyn TradingStrategy>;
+}
+
+impl Clone for Box<dyn TradingStrategy> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Long below the threshold, short at or above it
+#[derive(Debug, Clone)]
+pub struct ThresholdCrossing {
+    /// Signal threshold
+    pub threshold: f64,
+}
+
+impl TradingStrategy for ThresholdCrossing {
+    fn name(&self) -> String {
+        "Threshold Crossing".to_string()
+    }
+
+    fn on_tick(&mut self, observation: &MarketObservation) -> StrategyAction {
+        if observation.bsi.value() < self.threshold {
+            StrategyAction::Target(1.0)
+        } else {
+            StrategyAction::Target(-1.0)
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Follow the BSI change over the last `lookback_periods` updates
+#[derive(Debug, Clone)]
+pub struct Momentum {
+    lookback_periods: usize,
+    window: VecDeque<BSI>,
+}
+
+impl Momentum {
+    /// Create a momentum strategy
+    pub fn new(lookback_periods: usize) -> Self {
+        Momentum {
+            lookback_periods,
+            window: VecDeque::with_capacity(lookback_periods + 1),
+        }
+    }
+}
+
+impl TradingStrategy for Momentum {
+    fn name(&self) -> String {
+        "Momentum".to_string()
+    }
+
+    fn on_tick(&mut self, observation: &MarketObservation) -> StrategyAction {
+        let signal = match self.window.front() {
+            Some(start) if self.window.len() >= self.lookback_periods => {
+                (observation.bsi.value() - start.value()).clamp(-1.0, 1.0)
+            }
+            _ => 0.0,
+        };
+
+        self.window.push_back(observation.bsi);
+        if self.window.len() > self.lookback_periods {
+            self.window.pop_front();
+        }
+
+        StrategyAction::Target(signal)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Bet on a return to `mean` once the BSI strays more than `deviation`
+#[derive(Debug, Clone)]
+pub struct MeanReversion {
+    /// Long-run mean
+    pub mean: f64,
+    /// Band around the mean with no position
+    pub deviation: f64,
+}
+
+impl TradingStrategy for MeanReversion {
+    fn name(&self) -> String {
+        "Mean Reversion".to_string()
+    }
+
+    fn on_tick(&mut self, observation: &MarketObservation) -> StrategyAction {
+        let distance = observation.bsi.value() - self.mean;
+        if distance.abs() > self.deviation {
+            StrategyAction::Target(-distance.signum())
+        } else {
+            StrategyAction::Target(0.0)
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Short above the threshold, long at or below it
+#[derive(Debug, Clone)]
+pub struct Contrarian {
+    /// Signal threshold
+    pub threshold: f64,
+}
+
+impl TradingStrategy for Contrarian {
+    fn name(&self) -> String {
+        "Contrarian".to_string()
+    }
+
+    fn on_tick(&mut self, observation: &MarketObservation) -> StrategyAction {
+        if observation.bsi.value() > self.threshold {
+            StrategyAction::Target(-1.0)
+        } else {
+            StrategyAction::Target(1.0)
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Placeholder for [`Strategy::Custom`] that never trades
+#[derive(Debug, Clone)]
+pub struct Passive {
+    /// Strategy name
+    pub name: String,
+}
+
+impl TradingStrategy for Passive {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn on_tick(&mut self, _observation: &MarketObservation) -> StrategyAction {
+        StrategyAction::Hold
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Converts strategy actions into orders
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PositionSizing {
+    /// Fraction of equity committed at a full-strength target
+    pub max_position_fraction: f64,
+    /// Minimum change in position value, as a fraction of equity, worth trading
+    pub rebalance_threshold: f64,
+}
+
+impl Default for PositionSizing {
+    fn default() -> Self {
+        PositionSizing {
+            max_position_fraction: 0.5,
+            rebalance_threshold: 0.05,
+        }
+    }
+}
+
+impl PositionSizing {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.max_position_fraction) {
+            return Err(SimulatorError::InvalidConfig(
+                "Max position fraction must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.rebalance_threshold) {
+            return Err(SimulatorError::InvalidConfig(
+                "Rebalance threshold must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Order carrying out `action` as `(side, contracts)`, if any
+    pub fn order(
+        &self,
+        action: StrategyAction,
+        observation: &MarketObservation,
+    ) -> Option<(PositionType, f64)> {
+        match action {
+            StrategyAction::Hold => None,
+            StrategyAction::Order { side, size } => {
+                // Contracts reducing an opposite position pay out; the rest need capital
+                let reducible = match side {
+                    PositionType::Long => (-observation.position).max(0.0),
+                    PositionType::Short => observation.position.max(0.0),
+                };
+                let unit_price = side.unit_price(observation.market_price);
+                let size = size.min(reducible + observation.capital.max(0.0) / unit_price);
+                (size > 0.0).then_some((side, size))
+            }
+            StrategyAction::Target(signal) => {
+                let price = observation.market_price;
+                let signal = signal.clamp(-1.0, 1.0);
+                let equity = observation.equity.max(0.0);
+                let side = if signal >= 0.0 { PositionType::Long } else { PositionType::Short };
+                let unit_price = side.unit_price(price);
+                let target = if unit_price > MIN_UNIT_PRICE {
+                    signal * self.max_position_fraction * equity / unit_price
+                } else {
+                    0.0
+                };
+
+                let delta = target - observation.position;
+                let side = if delta >= 0.0 { PositionType::Long } else { PositionType::Short };
+                let worth_trading =
+                    delta.abs() * side.unit_price(price) >= self.rebalance_threshold * equity;
+                (worth_trading && delta != 0.0).then_some((side, delta.abs()))
+            }
+        }
+    }
 }
 
 /// Strategy backtest results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StrategyBacktest {
     /// Strategy name
     pub strategy_name: String,
-    /// Total return
+    /// Total return, net of fees
     pub total_return: f64,
+    /// Total return before fees
+    pub gross_return: f64,
+    /// Fees paid, in the same units as the returns
+    pub total_fees: f64,
     /// Number of trades
     pub num_trades: usize,
     /// Win rate
@@ -87,6 +382,23 @@ pub struct StrategyBacktest {
     pub sharpe_ratio: f64,
     /// Maximum drawdown
     pub max_drawdown: f64,
+    /// Market outcome the backtest settled against (None = not settled)
+    pub outcome: Option<SettlementOutcome>,
+    /// Equity after each update, ending with the settlement point
+    pub equity_curve: Vec<EquityPoint>,
+    /// Trades executed by the strategy
+    pub trades: Vec<Trade>,
+}
+
+/// Strategy equity at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    /// Update time
+    pub timestamp: DateTime<Utc>,
+    /// Capital plus open position marked at the BSI
+    pub equity: f64,
+    /// Net position in contracts (positive = long, negative = short)
+    pub position: f64,
 }
 
 impl StrategyBacktest {
@@ -95,11 +407,70 @@ impl StrategyBacktest {
         StrategyBacktest {
             strategy_name,
             total_return: 0.0,
+            gross_return: 0.0,
+            total_fees: 0.0,
             num_trades: 0,
             win_rate: 0.0,
             sharpe_ratio: 0.0,
             max_drawdown: 0.0,
+            outcome: None,
+            equity_curve: Vec::new(),
+            trades: Vec::new(),
+        }
+    }
+
+    /// Summarize a settled strategy run from its equity curve and trades
+    ///
+    /// Sharpe ratio uses per-update equity returns, skipping updates that
+    /// start from non-positive equity; drawdown is the largest peak-to-trough
+    /// fall in equity relative to the peak; win rate counts trades that
+    /// closed contracts at a profit.
+    pub fn from_equity_curve(
+        strategy_name: String,
+        initial_capital: f64,
+        fees_paid: f64,
+        outcome: SettlementOutcome,
+        equity_curve: Vec<EquityPoint>,
+        trades: Vec<Trade>,
+    ) -> Self {
+        let mut backtest = StrategyBacktest::new(strategy_name);
+        let returns: Vec<f64> = equity_curve
+            .windows(2)
+            .filter(|pair| pair[0].equity > 0.0)
+            .map(|pair| pair[1].equity / pair[0].equity - 1.0)
+            .collect();
+        backtest.calculate_metrics(&returns);
+
+        let mut peak = initial_capital;
+        let mut max_drawdown: f64 = 0.0;
+        for point in &equity_curve {
+            peak = peak.max(point.equity);
+            if peak > 0.0 {
+                max_drawdown = max_drawdown.max((peak - point.equity) / peak);
+            }
         }
+        backtest.max_drawdown = max_drawdown;
+
+        let closed: Vec<f64> = trades
+            .iter()
+            .filter(|t| matches!(t.trade_type, TradeType::Decrease | TradeType::Close))
+            .map(|t| t.realized_pnl)
+            .collect();
+        backtest.win_rate = if closed.is_empty() {
+            0.0
+        } else {
+            closed.iter().filter(|&&pnl| pnl > 0.0).count() as f64 / closed.len() as f64
+        };
+
+        let final_equity = equity_curve.last().map_or(initial_capital, |p| p.equity);
+        backtest.total_return = final_equity / initial_capital - 1.0;
+        backtest.total_fees = fees_paid / initial_capital;
+        backtest.gross_return = backtest.total_return + backtest.total_fees;
+        backtest.num_trades = trades.len();
+        backtest.outcome = Some(outcome);
+        backtest.equity_curve = equity_curve;
+        backtest.trades = trades;
+        backtest
     }
 
     /// Calculate performance metrics
@@ -110,6 +481,8 @@ impl StrategyBacktest {
 
         // Total return
         self.total_return = returns.iter().sum();
+        self.gross_return = self.total_return;
+        self.total_fees = 0.0;
 
         // Win rate
         let wins = returns.iter().filter(|&&r| r > 0.0).count();
@@ -149,6 +522,15 @@ impl StrategyBacktest {
         self.max_drawdown = max_dd;
         self.num_trades = returns.len();
     }
+
+    /// Calculate performance metrics after paying a round-trip taker fee on every trade
+    pub fn calculate_metrics_net_of_fees(&mut self, returns: &[f64], fees: &FeeConfig) {
+        let cost = fees.round_trip_rate();
+        let net: Vec<f64> = returns.iter().map(|r| r - cost).collect();
+        self.calculate_metrics(&net);
+        self.gross_return = returns.iter().sum();
+        self.total_fees = cost * returns.len() as f64;
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +546,67 @@ mod tests {
         assert_eq!(signal, 1.0); // Should be long below threshold
     }
 
+    #[test]
+    fn test_momentum_matches_history_replay() {
+        let values = [0.5, 0.52, 0.55, 0.53, 0.6, 0.58];
+        let history: Vec<BSI> = values.iter().map(|&v| BSI::new(v).unwrap()).collect();
+        let enum_strategy = Strategy::Momentum { lookback_periods: 3 };
+        let mut stateful = enum_strategy.build();
+
+        for (i, &bsi) in history.iter().enumerate() {
+            let expected = enum_strategy.evaluate(bsi, &history[..i]);
+            let action = stateful.on_tick(&MarketObservation::from_bsi(bsi));
+            assert_eq!(action, StrategyAction::Target(expected));
+        }
+        assert!((enum_strategy.evaluate(history[5], &history[..5]) - 0.03).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_explicit_orders_capped_at_capital() {
+        let sizing = PositionSizing::default();
+        let observation = MarketObservation {
+            position: -30.0,
+            capital: 20.0,
+            ..MarketObservation::from_bsi(BSI::new(0.4).unwrap())
+        };
+        let order = |side, size| sizing.order(StrategyAction::Order { side, size }, &observation);
+
+        // 30 contracts close the short, 20 / 0.4 open a long
+        assert_eq!(order(PositionType::Long, 1e6), Some((PositionType::Long, 80.0)));
+        assert_eq!(order(PositionType::Long, 10.0), Some((PositionType::Long, 10.0)));
+        // Shorting more is paid at 0.6 per NO contract
+        let (_, size) = order(PositionType::Short, 1e6).unwrap();
+        assert!((size - 20.0 / 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_equity_curve_drawdown_and_wiped_out_equity() {
+        let start = default_start_time();
+        let curve: Vec<EquityPoint> = [120.0, 60.0, 0.0, 0.0, 30.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &equity)| EquityPoint {
+                timestamp: start + chrono::Duration::hours(i as i64),
+                equity,
+                position: 0.0,
+            })
+            .collect();
+
+        let backtest = StrategyBacktest::from_equity_curve(
+            "Test Strategy".to_string(),
+            100.0,
+            0.0,
+            SettlementOutcome::Expired,
+            curve,
+            Vec::new(),
+        );
+
+        // Peak of 120 falls all the way to zero
+        assert!((backtest.max_drawdown - 1.0).abs() < 1e-9);
+        assert!(backtest.sharpe_ratio.is_finite());
+        assert!((backtest.total_return + 0.7).abs() < 1e-9);
+    }
+
     #[test]
     fn test_backtest_metrics() {
         let mut backtest = StrategyBacktest::new("Test Strategy".to_string());
@@ -175,4 +618,21 @@ mod tests {
         assert!(backtest.win_rate > 0.0);
         assert_eq!(backtest.num_trades, 5);
     }
+
+    #[test]
+    fn test_backtest_net_of_fees() {
+        let mut backtest = StrategyBacktest::new("Test Strategy".to_string());
+        let fees = FeeConfig {
+            taker_fee_bps: 50,
+            ..FeeConfig::default()
+        };
+
+        backtest.calculate_metrics_net_of_fees(&[0.1, 0.005, -0.05], &fees);
+
+        assert!((backtest.gross_return - 0.055).abs() < 1e-9);
+        assert!((backtest.total_fees - 0.03).abs() < 1e-9);
+        assert!((backtest.total_return - 0.025).abs() < 1e-9);
+        // The small winner becomes a loser after fees
+        assert!((backtest.win_rate - 1.0 / 3.0).abs() < 1e-9);
+    }
 }
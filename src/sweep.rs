@@ -0,0 +1,514 @@
+//! Parameter sweeps over simulation configurations
+//!
+//! Parameters are addressed by their dotted path in the serialized
+//! [`SimulationConfig`] (`threshold`, `oracle.noise_level`,
+//! `participant_mix.Momentum`), plus `scenario` for the scenario run. A mix
+//! weight swept on an unset mix overrides one of the default equal weights.
+//! Points are generated on a full grid or by Latin hypercube sampling, and
+//! every point runs the same replicate seeds so that differences between
+//! points are not masked by sampling noise.
+
+use crate::analytics::PerformanceMetrics;
+use crate::config::SimulationConfig;
+use crate::error::{Result, SimulatorError};
+use crate::montecarlo::{MonteCarloConfig, MonteCarloRunner, MonteCarloSummary};
+use crate::participant::ParticipantBehavior;
+use crate::rng::{RngStreams, SWEEP_STREAM};
+use crate::scenario::Scenario;
+use crate::simulator::{SimulationResult, Simulator};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Parameter path selecting the scenario instead of a config field
+pub const SCENARIO_PARAMETER: &str = "scenario";
+
+/// Values a swept parameter takes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Axis {
+    /// Explicit values, in JSON form
+    Values(Vec<Value>),
+    /// `steps` evenly spaced grid values from `min` to `max`, or uniform
+    /// draws from `[min, max]` when sampled
+    Range { min: f64, max: f64, steps: usize },
+}
+
+impl Axis {
+    /// Explicit numeric values
+    pub fn list(values: &[f64]) -> Self {
+        Axis::Values(values.iter().map(|v| Value::from(*v)).collect())
+    }
+
+    /// Each of `scenarios`
+    pub fn scenarios(scenarios: &[Scenario]) -> Self {
+        Axis::Values(
+            scenarios
+                .iter()
+                .map(|s| Value::String(format!("{:?}", s)))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        let valid = match self {
+            Axis::Values(values) => !values.is_empty(),
+            Axis::Range { min, max, steps } => {
+                min.is_finite() && max.is_finite() && min <= max && *steps > 0
+            }
+        };
+        if !valid {
+            return Err(SimulatorError::InvalidConfig(
+                "Sweep axes need values, or a finite range with min <= max and steps > 0"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn grid(&self) -> Vec<Value> {
+        match self {
+            Axis::Values(values) => values.clone(),
+            Axis::Range { min, max, steps } => (0..*steps)
+                .map(|i| match steps {
+                    1 => *min,
+                    _ => min + (max - min) * i as f64 / (steps - 1) as f64,
+                })
+                .map(Value::from)
+                .collect(),
+        }
+    }
+
+    /// Value at quantile `u` in `[0, 1]`
+    pub(crate) fn sample(&self, u: f64) -> Value {
+        match self {
+            Axis::Values(values) => {
+                values[((u * values.len() as f64) as usize).min(values.len() - 1)].clone()
+            }
+            Axis::Range { min, max, .. } => Value::from(min + (max - min) * u),
+        }
+    }
+}
+
+/// One configuration in a sweep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepPoint {
+    /// Swept parameter values, in parameter order
+    pub values: Vec<Value>,
+    /// Scenario run at this point
+    pub scenario: Scenario,
+    /// Configuration run at this point
+    pub config: SimulationConfig,
+}
+
+/// Metrics of one sweep point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepRow {
+    /// Swept parameter values, in parameter order
+    pub values: Vec<Value>,
+    /// Metrics over the point's replicates
+    pub metrics: PerformanceMetrics,
+}
+
+/// Tidy table of sweep results, one row per point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepTable {
+    /// Swept parameter paths
+    pub parameters: Vec<String>,
+    /// Rows in point order
+    pub rows: Vec<SweepRow>,
+}
+
+/// Metric columns of the CSV export
+const METRIC_COLUMNS: [&str; 10] = [
+    "total_simulations",
+    "successful_resolutions",
+    "resolution_rate",
+    "avg_final_bsi",
+    "avg_volume",
+    "avg_trades",
+    "avg_duration_days",
+    "bsi_volatility",
+    "avg_fees",
+    "avg_protocol_revenue",
+];
+
+impl SweepTable {
+    /// Render as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = self
+            .parameters
+            .iter()
+            .map(|p| csv_field(p))
+            .chain(METRIC_COLUMNS.iter().map(|c| c.to_string()))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push('\n');
+
+        for row in &self.rows {
+            let m = &row.metrics;
+            let metrics = [
+                m.total_simulations as f64,
+                m.successful_resolutions as f64,
+                m.resolution_rate,
+                m.avg_final_bsi,
+                m.avg_volume,
+                m.avg_trades,
+                m.avg_duration_days,
+                m.bsi_volatility,
+                m.avg_fees,
+                m.avg_protocol_revenue,
+            ];
+            let fields: Vec<String> = row
+                .values
+                .iter()
+                .map(|value| match value {
+                    Value::String(text) => csv_field(text),
+                    value => csv_field(&value.to_string()),
+                })
+                .chain(metrics.iter().map(|v| v.to_string()))
+                .collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Write the CSV rendering to a file
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Sweep over configuration parameters
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    base: SimulationConfig,
+    scenario: Scenario,
+    parameters: Vec<(String, Axis)>,
+    replicates: usize,
+    master_seed: u64,
+    max_parallel: usize,
+}
+
+impl Sweep {
+    /// Sweep around `base`, running `scenario` unless the scenario is swept
+    pub fn new(base: SimulationConfig, scenario: Scenario) -> Self {
+        Sweep {
+            base,
+            scenario,
+            parameters: Vec::new(),
+            replicates: 10,
+            master_seed: 0,
+            max_parallel: 0,
+        }
+    }
+
+    /// Vary the parameter at `path`
+    ///
+    /// The path must exist in the serialized base configuration, so optional
+    /// sections such as `oracle` need to be set before their fields are swept.
+    pub fn vary(mut self, path: impl Into<String>, axis: Axis) -> Self {
+        self.parameters.push((path.into(), axis));
+        self
+    }
+
+    /// Set the number of replicate runs per point
+    pub fn replicates(mut self, replicates: usize) -> Self {
+        self.replicates = replicates;
+        self
+    }
+
+    /// Set the seed from which replicate seeds and samples are derived
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.master_seed = seed;
+        self
+    }
+
+    /// Set the number of runs executing at once; 0 uses every available core
+    pub fn max_parallel(mut self, max_parallel: usize) -> Self {
+        self.max_parallel = max_parallel;
+        self
+    }
+
+    /// Swept parameter paths, in order
+    pub fn parameters(&self) -> Vec<String> {
+        self.parameters
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// Every combination of the axes' grid values
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an axis is empty or a value does not produce a
+    /// valid configuration
+    pub fn grid(&self) -> Result<Vec<SweepPoint>> {
+        let mut combinations: Vec<Vec<Value>> = vec![Vec::new()];
+        for (_, axis) in &self.parameters {
+            axis.validate()?;
+            combinations = combinations
+                .into_iter()
+                .flat_map(|prefix| {
+                    axis.grid().into_iter().map(move |value| {
+                        let mut values = prefix.clone();
+                        values.push(value);
+                        values
+                    })
+                })
+                .collect();
+        }
+
+        combinations
+            .into_iter()
+            .map(|values| self.point(values))
+            .collect()
+    }
+
+    /// `samples` points by Latin hypercube sampling
+    ///
+    /// Each axis is split into `samples` equal strata, every stratum is
+    /// sampled exactly once, and strata are paired across axes at random.
+    pub fn latin_hypercube(&self, samples: usize) -> Result<Vec<SweepPoint>> {
+        let mut rng = RngStreams::new(self.master_seed).stream(SWEEP_STREAM);
+        let mut columns = Vec::with_capacity(self.parameters.len());
+        for (_, axis) in &self.parameters {
+            axis.validate()?;
+            let mut strata: Vec<usize> = (0..samples).collect();
+            strata.shuffle(&mut rng);
+            let column: Vec<Value> = strata
+                .into_iter()
+                .map(|stratum| axis.sample((stratum as f64 + rng.gen::<f64>()) / samples as f64))
+                .collect();
+            columns.push(column);
+        }
+
+        (0..samples)
+            .map(|i| self.point(columns.iter().map(|column| column[i].clone()).collect()))
+            .collect()
+    }
+
+    /// Run every point with the sweep's replicates
+    ///
+    /// Replicate `k` uses the same seed at every point.
+    pub async fn run(&self, points: &[SweepPoint]) -> Result<SweepTable> {
+        let mut rows = Vec::with_capacity(points.len());
+        for point in points {
+            let summary = self.run_point(point, |_| {}).await?;
+            rows.push(SweepRow {
+                values: point.values.clone(),
+                metrics: summary.metrics,
+            });
+        }
+
+        Ok(SweepTable {
+            parameters: self.parameters(),
+            rows,
+        })
+    }
+
+    /// Run one point's replicates, passing each result to `on_result`
+    pub(crate) async fn run_point(
+        &self,
+        point: &SweepPoint,
+        on_result: impl FnMut(&SimulationResult),
+    ) -> Result<MonteCarloSummary> {
+        let batch = MonteCarloConfig {
+            runs: self.replicates,
+            master_seed: self.master_seed,
+            max_parallel: self.max_parallel,
+        };
+        let runner = MonteCarloRunner::new(Simulator::new(point.config.clone()), batch)?;
+        runner.run(point.scenario, on_result).await
+    }
+
+    /// Point at position `unit` in `[0, 1]` along each axis
+    pub(crate) fn unit_point(&self, unit: &[f64]) -> Result<SweepPoint> {
+        let mut values = Vec::with_capacity(self.parameters.len());
+        for ((_, axis), u) in self.parameters.iter().zip(unit) {
+            axis.validate()?;
+            values.push(axis.sample(*u));
+        }
+        self.point(values)
+    }
+
+    /// Apply parameter values to the base configuration
+    pub(crate) fn point(&self, values: Vec<Value>) -> Result<SweepPoint> {
+        let mut config = serde_json::to_value(&self.base)?;
+        let mut scenario = self.scenario;
+        for ((path, _), value) in self.parameters.iter().zip(&values) {
+            if path == SCENARIO_PARAMETER {
+                scenario = serde_json::from_value(value.clone())?;
+            } else {
+                set_path(&mut config, path, value)?;
+            }
+        }
+
+        let config: SimulationConfig = serde_json::from_value(config)?;
+        config.validate()?;
+        Ok(SweepPoint {
+            values,
+            scenario,
+            config,
+        })
+    }
+}
+
+/// Set the field at a dotted path, keeping integer fields integral
+fn set_path(root: &mut Value, path: &str, value: &Value) -> Result<()> {
+    let missing = || SimulatorError::InvalidConfig(format!("no config field at {}", path));
+    // Mix weights may be added to a partial mix, or to an unset mix, which
+    // starts from the default equal weights
+    let is_mix = path.starts_with("participant_mix.");
+    let mut field = root;
+    for key in path.split('.') {
+        if is_mix && field.is_null() {
+            let mix: BTreeMap<ParticipantBehavior, f64> = ParticipantBehavior::all()
+                .into_iter()
+                .map(|behavior| (behavior, 1.0))
+                .collect();
+            *field = serde_json::to_value(mix)?;
+        }
+        let Value::Object(map) = field else {
+            return Err(missing());
+        };
+        field = if is_mix {
+            map.entry(key.to_string()).or_insert(Value::Null)
+        } else {
+            map.get_mut(key).ok_or_else(missing)?
+        };
+    }
+
+    *field = match (&*field, value.as_f64()) {
+        (current, Some(number)) if current.is_u64() || current.is_i64() => {
+            Value::from(number.round() as i64)
+        }
+        _ => value.clone(),
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::OracleConfig;
+
+    fn base() -> SimulationConfig {
+        SimulationConfig::builder()
+            .duration_days(2)
+            .num_participants(10)
+            .oracle(OracleConfig::default())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_grid_covers_every_combination() {
+        let sweep = Sweep::new(base(), Scenario::Sideways)
+            .vary(
+                "persistence_hours",
+                Axis::Range {
+                    min: 6.0,
+                    max: 24.0,
+                    steps: 3,
+                },
+            )
+            .vary("oracle.noise_level", Axis::list(&[0.01, 0.05]))
+            .vary(
+                SCENARIO_PARAMETER,
+                Axis::scenarios(&[Scenario::BullishTrend]),
+            );
+
+        let points = sweep.grid().unwrap();
+
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[2].config.persistence_hours, 15);
+        assert_eq!(points[3].config.oracle.as_ref().unwrap().noise_level, 0.05);
+        assert!(points.iter().all(|p| p.scenario == Scenario::BullishTrend));
+        assert!(Sweep::new(base(), Scenario::Sideways)
+            .vary("oracle.missing", Axis::list(&[1.0]))
+            .grid()
+            .is_err());
+    }
+
+    #[test]
+    fn test_latin_hypercube_hits_every_stratum() {
+        let sweep = Sweep::new(base(), Scenario::Sideways)
+            .vary(
+                "threshold",
+                Axis::Range {
+                    min: 0.6,
+                    max: 0.9,
+                    steps: 1,
+                },
+            )
+            .vary(
+                "volatility",
+                Axis::Range {
+                    min: 0.0,
+                    max: 0.5,
+                    steps: 1,
+                },
+            )
+            .seed(3);
+
+        let points = sweep.latin_hypercube(10).unwrap();
+
+        for (path, min, width) in [("threshold", 0.6, 0.03), ("volatility", 0.0, 0.05)] {
+            let mut strata: Vec<usize> = points
+                .iter()
+                .map(|p| {
+                    let value = serde_json::to_value(&p.config).unwrap()[path]
+                        .as_f64()
+                        .unwrap();
+                    ((value - min) / width) as usize
+                })
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..10).collect::<Vec<_>>());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sweep_table_exports_csv() {
+        let sweep = Sweep::new(base(), Scenario::Sideways)
+            .vary("participant_mix.Aggressive", Axis::list(&[1.0, 3.0]))
+            .replicates(2)
+            .seed(1);
+
+        let table = sweep.run(&sweep.grid().unwrap()).await.unwrap();
+        let csv = table.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(table.rows.len(), 2);
+        assert!(table
+            .rows
+            .iter()
+            .all(|row| row.metrics.total_simulations == 2));
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("participant_mix.Aggressive,total_simulations,"));
+        assert!(lines[2].starts_with("3.0,2,"));
+        // The swept weight reshapes a crowd that otherwise keeps equal weights
+        assert_ne!(table.rows[0].metrics, table.rows[1].metrics);
+        let mix = sweep.grid().unwrap()[1].config.participant_mix.clone().unwrap();
+        assert_eq!(mix.len(), ParticipantBehavior::all().len());
+        assert_eq!(mix[&ParticipantBehavior::Aggressive], 3.0);
+        assert_eq!(mix[&ParticipantBehavior::Rational], 1.0);
+    }
+}
@@ -0,0 +1,233 @@
+//! Per-tick time series capture
+
+use crate::error::{Result, SimulatorError};
+use crate::market::MarketState;
+use crate::types::BSI;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Time series capture configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeSeriesConfig {
+    /// Record one point every N ticks
+    pub sample_every: u32,
+    /// Maximum number of points kept; older points are merged pairwise when exceeded
+    pub max_points: Option<usize>,
+}
+
+impl Default for TimeSeriesConfig {
+    fn default() -> Self {
+        TimeSeriesConfig {
+            sample_every: 1,
+            max_points: Some(10_000),
+        }
+    }
+}
+
+impl TimeSeriesConfig {
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        if self.sample_every == 0 {
+            return Err(SimulatorError::InvalidConfig(
+                "Time series sample interval must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.max_points.is_some_and(|max| max < 2) {
+            return Err(SimulatorError::InvalidConfig(
+                "Time series must keep at least 2 points".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Market snapshot covering one or more simulation ticks
+///
+/// Flow quantities (trades, volume, shocks) are summed over the covered ticks;
+/// level quantities (BSI, open interest, state) are taken from the last tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickSnapshot {
+    /// Time of the last covered tick
+    pub timestamp: DateTime<Utc>,
+    /// Oracle BSI at the last covered tick
+    pub bsi: f64,
+    /// Latent belief at the last covered tick
+    pub latent_bsi: f64,
+    /// Market YES price at the last covered tick
+    pub market_price: f64,
+    /// Sum of shocks applied during the covered ticks
+    pub shock_applied: Option<f64>,
+    /// Trades executed during the covered ticks
+    pub trades: usize,
+    /// Volume traded during the covered ticks
+    pub volume: f64,
+    /// Open interest at the last covered tick
+    pub open_interest: f64,
+    /// Market state at the last covered tick
+    pub state: MarketState,
+    /// Number of ticks covered by this snapshot
+    pub ticks: u32,
+}
+
+impl TickSnapshot {
+    /// Fold a later snapshot into this one
+    fn merge(&mut self, later: TickSnapshot) {
+        self.shock_applied = match (self.shock_applied, later.shock_applied) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+        self.trades += later.trades;
+        self.volume += later.volume;
+        self.timestamp = later.timestamp;
+        self.bsi = later.bsi;
+        self.latent_bsi = later.latent_bsi;
+        self.market_price = later.market_price;
+        self.open_interest = later.open_interest;
+        self.state = later.state;
+        self.ticks += later.ticks;
+    }
+}
+
+/// Recorded time series of a simulation run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeSeries {
+    /// Number of ticks per point after downsampling
+    pub ticks_per_point: u32,
+    /// Recorded points in time order
+    pub points: Vec<TickSnapshot>,
+}
+
+impl TimeSeries {
+    /// Get the BSI path as `(timestamp, BSI)` pairs
+    pub fn bsi_path(&self) -> Vec<(DateTime<Utc>, BSI)> {
+        self.points
+            .iter()
+            .filter_map(|point| BSI::new(point.bsi).ok().map(|bsi| (point.timestamp, bsi)))
+            .collect()
+    }
+}
+
+/// Incremental, memory-bounded time series recorder
+#[derive(Debug)]
+pub struct TimeSeriesRecorder {
+    config: TimeSeriesConfig,
+    ticks_per_point: u32,
+    pending: Option<TickSnapshot>,
+    points: Vec<TickSnapshot>,
+}
+
+impl TimeSeriesRecorder {
+    /// Create a recorder
+    pub fn new(config: TimeSeriesConfig) -> Self {
+        TimeSeriesRecorder {
+            config,
+            ticks_per_point: config.sample_every,
+            pending: None,
+            points: Vec::new(),
+        }
+    }
+
+    /// Record a single-tick snapshot
+    pub fn record(&mut self, snapshot: TickSnapshot) {
+        match self.pending.as_mut() {
+            Some(pending) => pending.merge(snapshot),
+            None => self.pending = Some(snapshot),
+        }
+
+        if self.pending.as_ref().is_some_and(|p| p.ticks >= self.ticks_per_point) {
+            self.flush();
+        }
+    }
+
+    /// Finish recording, flushing any partial point
+    pub fn finish(mut self) -> TimeSeries {
+        self.flush();
+        TimeSeries {
+            ticks_per_point: self.ticks_per_point,
+            points: self.points,
+        }
+    }
+
+    fn flush(&mut self) {
+        let Some(point) = self.pending.take() else {
+            return;
+        };
+        self.points.push(point);
+
+        if let Some(max_points) = self.config.max_points {
+            if self.points.len() >= max_points {
+                self.compact();
+            }
+        }
+    }
+
+    /// Merge adjacent points pairwise and double the sampling stride
+    fn compact(&mut self) {
+        let mut merged = Vec::with_capacity(self.points.len() / 2 + 1);
+        let mut points = std::mem::take(&mut self.points).into_iter();
+        while let Some(mut first) = points.next() {
+            if let Some(second) = points.next() {
+                first.merge(second);
+            }
+            merged.push(first);
+        }
+        self.points = merged;
+        self.ticks_per_point = self.ticks_per_point.saturating_mul(2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::default_start_time;
+    use chrono::Duration;
+
+    fn tick(i: i64) -> TickSnapshot {
+        TickSnapshot {
+            timestamp: default_start_time() + Duration::minutes(5 * i),
+            bsi: 0.5,
+            latent_bsi: 0.5,
+            market_price: 0.5,
+            shock_applied: None,
+            trades: 2,
+            volume: 10.0,
+            open_interest: 0.0,
+            state: MarketState::Active,
+            ticks: 1,
+        }
+    }
+
+    #[test]
+    fn test_downsampling_preserves_flows() {
+        let mut recorder = TimeSeriesRecorder::new(TimeSeriesConfig {
+            sample_every: 3,
+            max_points: None,
+        });
+        for i in 0..10 {
+            recorder.record(tick(i));
+        }
+        let series = recorder.finish();
+
+        assert_eq!(series.points.len(), 4);
+        assert_eq!(series.points.iter().map(|p| p.trades).sum::<usize>(), 20);
+        assert_eq!(series.points[3].ticks, 1);
+    }
+
+    #[test]
+    fn test_max_points_bounds_memory() {
+        let mut recorder = TimeSeriesRecorder::new(TimeSeriesConfig {
+            sample_every: 1,
+            max_points: Some(16),
+        });
+        for i in 0..1000 {
+            recorder.record(tick(i));
+        }
+        let series = recorder.finish();
+
+        assert!(series.points.len() < 16);
+        assert_eq!(series.points.iter().map(|p| p.ticks).sum::<u32>(), 1000);
+        assert_eq!(series.points.last().unwrap().timestamp, tick(999).timestamp);
+    }
+}
@@ -48,9 +48,9 @@ impl Default for BSI {
 pub struct Position {
     /// Participant ID
     pub participant_id: String,
-    /// Position size
+    /// Position size in contracts
     pub size: f64,
-    /// Entry price
+    /// Average entry price, quoted as the YES (long) price
     pub entry_price: f64,
     /// Entry time
     pub entry_time: DateTime<Utc>,
@@ -58,6 +58,23 @@ pub struct Position {
     pub position_type: PositionType,
 }
 
+impl Position {
+    /// Capital paid to build the position
+    pub fn cost_basis(&self) -> f64 {
+        self.size * self.position_type.unit_price(self.entry_price)
+    }
+
+    /// Value of the position marked at the given YES price
+    pub fn mark_value(&self, price: f64) -> f64 {
+        self.size * self.position_type.unit_price(price)
+    }
+
+    /// Unrealized profit and loss at the given YES price
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        self.mark_value(price) - self.cost_basis()
+    }
+}
+
 /// Type of position
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PositionType {
@@ -67,8 +84,26 @@ pub enum PositionType {
     Short,
 }
 
+impl PositionType {
+    /// Get the opposite side
+    pub fn opposite(&self) -> PositionType {
+        match self {
+            PositionType::Long => PositionType::Short,
+            PositionType::Short => PositionType::Long,
+        }
+    }
+
+    /// Price of one contract on this side given the YES price
+    pub fn unit_price(&self, price: f64) -> f64 {
+        match self {
+            PositionType::Long => price,
+            PositionType::Short => 1.0 - price,
+        }
+    }
+}
+
 /// A trade execution
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Trade {
     /// Trade ID
     pub id: String,
@@ -76,6 +111,8 @@ pub struct Trade {
     pub participant_id: String,
     /// Trade type
     pub trade_type: TradeType,
+    /// Side of the position affected by the trade
+    pub position_type: PositionType,
     /// Trade size
     pub size: f64,
     /// Execution price
@@ -84,6 +121,42 @@ pub struct Trade {
     pub timestamp: DateTime<Utc>,
     /// Current BSI at trade time
     pub bsi_at_trade: BSI,
+    /// Profit and loss realized by the trade
+    pub realized_pnl: f64,
+    /// Extra cost paid versus the pre-trade price
+    pub slippage: f64,
+    /// Trading fee paid on top of the trade notional
+    pub fee: f64,
+    /// Order book order that produced the trade (None = instant fill)
+    pub order_id: Option<u64>,
+    /// Whether the order added or removed book liquidity (None = instant fill)
+    pub liquidity_role: Option<LiquidityRole>,
+    /// Whether the order had size left unfilled after this trade
+    pub partial_fill: bool,
+}
+
+impl Trade {
+    /// Collateral value of the contracts traded
+    pub fn notional(&self) -> f64 {
+        self.size * self.position_type.unit_price(self.price)
+    }
+
+    /// Cash received by the participant before fees (negative when paying)
+    pub fn cash_flow(&self) -> f64 {
+        match self.trade_type {
+            TradeType::Open | TradeType::Increase => -self.notional(),
+            TradeType::Decrease | TradeType::Close => self.notional(),
+        }
+    }
+}
+
+/// Whether a fill added or removed order book liquidity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiquidityRole {
+    /// Resting order that was filled
+    Maker,
+    /// Incoming order that crossed the book
+    Taker,
 }
 
 /// Type of trade
@@ -123,6 +196,11 @@ impl TimeInterval {
     pub fn duration_days(&self) -> i64 {
         (self.end - self.start).num_days()
     }
+
+    /// Check if a time falls within the interval (inclusive)
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        time >= self.start && time <= self.end
+    }
 }
 
 #[cfg(test)]
@@ -145,6 +223,20 @@ mod tests {
         assert!(curr.crossed_threshold(prev, 0.5));
     }
 
+    #[test]
+    fn test_position_mark_to_market() {
+        let short = Position {
+            participant_id: "p".to_string(),
+            size: 10.0,
+            entry_price: 0.6,
+            entry_time: crate::clock::default_start_time(),
+            position_type: PositionType::Short,
+        };
+
+        assert!((short.cost_basis() - 4.0).abs() < 1e-10);
+        assert!((short.unrealized_pnl(0.5) - 1.0).abs() < 1e-10);
+    }
+
     #[test]
     fn test_bsi_distance() {
         let bsi = BSI::new(0.7).unwrap();